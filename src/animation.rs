@@ -0,0 +1,135 @@
+//! Animated transitions when a key moves on to a new phase
+//!
+//! The last frame rendered for each phase is kept so the first frames of the
+//! next one can be blended from it while the widget polls at a fast interval.
+
+use std::time::{Duration, Instant};
+
+use verandah_plugin::utils::prelude::*;
+
+use crate::render::{self, TransitionStyle};
+
+/// Transition state of one key
+pub struct Animation {
+    style: TransitionStyle,
+    length: Duration,
+    // Transitions the timer had made as of the last poll
+    transitions: Option<u64>,
+    // Last frame rendered, the starting point of the next transition
+    last_frame: Option<RgbImage>,
+    // Frame of the previous phase and when the transition away from it started
+    from: Option<(RgbImage, Instant)>,
+}
+
+impl Animation {
+    pub fn new(style: TransitionStyle, length: Duration) -> Self {
+        Animation {
+            style,
+            length,
+            transitions: None,
+            last_frame: None,
+            from: None,
+        }
+    }
+
+    /// Note how many phase transitions the timer has made, starting an
+    /// animation if it made another since the last poll
+    /// Returns how far through a running transition the key is (0.0 to 1.0)
+    pub fn observe(&mut self, transitions: u64) -> Option<f32> {
+        self.observe_at(transitions, Instant::now())
+    }
+
+    pub fn observe_at(&mut self, transitions: u64, now: Instant) -> Option<f32> {
+        if self.transitions.is_some_and(|seen| seen != transitions)
+            && let Some(frame) = self.last_frame.take()
+        {
+            self.from = Some((frame, now));
+        }
+        self.transitions = Some(transitions);
+        self.progress_at(now)
+    }
+
+    fn progress_at(&self, now: Instant) -> Option<f32> {
+        let (_, started) = self.from.as_ref()?;
+        let progress = now.duration_since(*started).as_secs_f32() / self.length.as_secs_f32();
+        (progress < 1.0).then_some(progress)
+    }
+
+    /// The frame to show for `frame`, blended from the previous phase while
+    /// a transition runs
+    pub fn frame(&mut self, frame: RgbImage, flash_color: Rgba<u8>) -> RgbImage {
+        self.frame_at(frame, flash_color, Instant::now())
+    }
+
+    pub fn frame_at(&mut self, frame: RgbImage, flash_color: Rgba<u8>, now: Instant) -> RgbImage {
+        if self.style == TransitionStyle::Off {
+            return frame;
+        }
+
+        let progress = self.progress_at(now);
+        let shown = match (progress, &self.from) {
+            (Some(t), Some((from, _))) => {
+                render::transition_frame(from, &frame, self.style, flash_color, t)
+            }
+            _ => frame.clone(),
+        };
+        if progress.is_none() {
+            self.from = None;
+        }
+        self.last_frame = Some(frame);
+        shown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filled(value: u8) -> RgbImage {
+        let mut image = RgbImage::new(2, 2);
+        for pixel in image.pixels_mut() {
+            pixel.0 = [value; 3];
+        }
+        image
+    }
+
+    #[test]
+    fn test_crossfade_on_transition() -> crate::error::Result<()> {
+        let white = rgb("#FFFFFF");
+        let mut animation = Animation::new(TransitionStyle::Crossfade, Duration::from_millis(400));
+        let start = Instant::now();
+        assert_eq!(animation.observe_at(0, start), None);
+        assert_eq!(
+            animation.frame_at(filled(0), white, start).get_pixel(0, 0)[0],
+            0
+        );
+
+        // A transition fades from the old phase's frame to the new one
+        assert_eq!(animation.observe_at(1, start), Some(0.0));
+        let halfway = start + Duration::from_millis(200);
+        assert_eq!(
+            animation
+                .frame_at(filled(200), white, halfway)
+                .get_pixel(1, 1)[2],
+            100
+        );
+
+        let done = start + Duration::from_millis(400);
+        assert_eq!(animation.observe_at(1, done), None);
+        assert_eq!(
+            animation.frame_at(filled(200), white, done).get_pixel(0, 0)[0],
+            200
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_transition_when_off() -> crate::error::Result<()> {
+        let mut animation = Animation::new(TransitionStyle::Off, Duration::from_millis(400));
+        let start = Instant::now();
+        animation.observe_at(0, start);
+        animation.frame_at(filled(0), rgb("#FFFFFF"), start);
+        assert_eq!(animation.observe_at(1, start), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,348 @@
+//! Continuous desktop status bar output for `verandah-pomodoroctl bar`
+//!
+//! Follows the running widget through an event subscription on the control
+//! socket and prints one line per update in the chosen bar's format.
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    os::unix::net::UnixStream,
+    thread,
+    time::Duration,
+};
+
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    cli::{BarArgs, BarFormat},
+    socket::{self, Status},
+};
+
+/// How long to wait before reconnecting when no widget is running
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// Binary invoked by polybar click actions
+const CTL_BINARY: &str = "verandah-pomodoroctl";
+
+/// Any protocol line carrying a status (subscribe response or event)
+#[derive(Deserialize)]
+struct StatusLine {
+    status: Option<Status>,
+}
+
+/// A click event from i3bar/swaybar
+#[derive(Deserialize)]
+struct ClickEvent {
+    button: u8,
+}
+
+/// Why following the widget stopped
+enum Stop {
+    /// The widget went away or could not be reached
+    Disconnected(io::Error),
+    /// The bar closed our output
+    OutputClosed(io::Error),
+}
+
+/// Follow the widget `instance` forever, reconnecting whenever it goes away
+/// Returns only when the output can no longer be written
+pub fn run(args: &BarArgs, instance: Option<&str>) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+
+    if args.format == BarFormat::I3bar {
+        writeln!(out, "{}", json!({"version": 1, "click_events": true}))?;
+        writeln!(out, "[")?;
+        out.flush()?;
+        spawn_click_reader(args.clone(), instance.map(str::to_string));
+    }
+
+    loop {
+        match follow(args, instance, &mut out) {
+            Err(Stop::OutputClosed(e)) => return Err(e),
+            Err(Stop::Disconnected(e)) => {
+                tracing::debug!(error = %e, "Widget not reachable, retrying");
+            }
+            Ok(()) => {}
+        }
+
+        write_line(&mut out, &format_line(args, instance, None))?;
+        thread::sleep(RECONNECT_DELAY);
+    }
+}
+
+/// Subscribe to the widget's events and print a line for each until it disconnects
+fn follow<W>(args: &BarArgs, instance: Option<&str>, out: &mut W) -> Result<(), Stop>
+where
+    W: Write,
+{
+    let path = socket::find_socket(instance).map_err(Stop::Disconnected)?;
+    let mut stream = UnixStream::connect(path).map_err(Stop::Disconnected)?;
+    stream
+        .write_all(b"{\"cmd\":\"subscribe\"}\n")
+        .map_err(Stop::Disconnected)?;
+
+    for line in BufReader::new(stream).lines() {
+        let line = line.map_err(Stop::Disconnected)?;
+        let Ok(StatusLine {
+            status: Some(status),
+        }) = serde_json::from_str::<StatusLine>(&line)
+        else {
+            tracing::debug!(line, "Ignoring line without status");
+            continue;
+        };
+
+        write_line(out, &format_line(args, instance, Some(&status))).map_err(Stop::OutputClosed)?;
+    }
+
+    Ok(())
+}
+
+fn write_line<W>(out: &mut W, line: &str) -> io::Result<()>
+where
+    W: Write,
+{
+    writeln!(out, "{line}")?;
+    // Bars read from a pipe, so every line must be flushed as it is produced
+    out.flush()
+}
+
+/// Send the configured verb for every i3bar click event read from stdin
+fn spawn_click_reader(args: BarArgs, instance: Option<String>) {
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines().map_while(Result::ok) {
+            let Some(verb) = click_verb(&args, &line) else {
+                continue;
+            };
+            if let Err(e) = socket::send_command(instance.as_deref(), verb) {
+                eprintln!("Error: failed to send {verb}: {e}");
+            }
+        }
+    });
+}
+
+/// Map an i3bar click event line to the verb configured for its button
+fn click_verb<'a>(args: &'a BarArgs, line: &str) -> Option<&'a str> {
+    // Click events arrive as elements of an endless JSON array
+    let line = line.trim().trim_start_matches(['[', ',']);
+    let event: ClickEvent = serde_json::from_str(line).ok()?;
+    match event.button {
+        1 => Some(args.on_click.as_str()),
+        2 => Some(args.on_middle_click.as_str()),
+        3 => Some(args.on_right_click.as_str()),
+        _ => None,
+    }
+}
+
+/// Format one output line for the widget `instance`; `None` means no widget is running
+fn format_line(args: &BarArgs, instance: Option<&str>, status: Option<&Status>) -> String {
+    let text = status
+        .map(|status| render_template(&args.template, status))
+        .unwrap_or_default();
+
+    match args.format {
+        BarFormat::Waybar => {
+            let value = match status {
+                Some(status) => json!({
+                    "text": text,
+                    "alt": status.phase,
+                    "tooltip": tooltip(status),
+                    "class": [status.phase.as_str(), status.kind.as_str(), state(status)],
+                    "percentage": percentage(status),
+                }),
+                None => json!({"text": "", "class": "disconnected"}),
+            };
+            value.to_string()
+        }
+        BarFormat::I3bar => {
+            let block_instance = status.map(|s| s.phase.as_str()).unwrap_or("disconnected");
+            let block = json!({
+                "name": "pomodoro",
+                "instance": block_instance,
+                "full_text": text,
+            });
+            format!("[{block}],")
+        }
+        BarFormat::Polybar if text.is_empty() => text,
+        BarFormat::Polybar => {
+            let ctl = match instance {
+                Some(id) => format!("{CTL_BINARY} --instance {id}"),
+                None => CTL_BINARY.to_string(),
+            };
+            format!(
+                "%{{A1:{}:}}%{{A2:{}:}}%{{A3:{}:}}{text}%{{A}}%{{A}}%{{A}}",
+                polybar_action(&ctl, &args.on_click),
+                polybar_action(&ctl, &args.on_middle_click),
+                polybar_action(&ctl, &args.on_right_click),
+            )
+        }
+    }
+}
+
+/// The polybar action running `ctl` for the socket verb `verb`
+fn polybar_action(ctl: &str, verb: &str) -> String {
+    // pomodoroctl takes a verb's argument as a word of its own (`extend 5m`)
+    let command = match verb.split_once(':') {
+        Some((verb, arg)) => format!("{ctl} {verb} {arg}"),
+        None => format!("{ctl} {verb}"),
+    };
+    // A bare colon would end the action early
+    command.replace(':', "\\:")
+}
+
+/// Substitute the status placeholders in `template`
+fn render_template(template: &str, status: &Status) -> String {
+    template
+        .replace("{phase}", &status.phase)
+        .replace("{kind}", &status.kind)
+        .replace("{remaining}", &status.remaining_formatted())
+        .replace("{remaining_secs}", &status.remaining_secs.to_string())
+        .replace("{state}", state(status))
+        .replace("{iterations}", &status.iterations.to_string())
+        .replace("{sessions}", &status.sessions_completed.to_string())
+        .replace("{percentage}", &percentage(status).to_string())
+}
+
+fn tooltip(status: &Status) -> String {
+    format!(
+        "{} ({}): {} remaining\nIterations: {}, sessions: {}",
+        status.phase,
+        state(status),
+        status.remaining_formatted(),
+        status.iterations,
+        status.sessions_completed
+    )
+}
+
+fn state(status: &Status) -> &'static str {
+    if status.running { "running" } else { "paused" }
+}
+
+/// Elapsed share of the current phase, 0 to 100
+fn percentage(status: &Status) -> u64 {
+    if status.duration_secs == 0 {
+        return 0;
+    }
+    let elapsed = status.duration_secs.saturating_sub(status.remaining_secs);
+    elapsed * 100 / status.duration_secs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(format: BarFormat) -> BarArgs {
+        BarArgs {
+            format,
+            template: "{phase} {remaining}".to_string(),
+            on_click: "toggle".to_string(),
+            on_middle_click: "reset".to_string(),
+            on_right_click: "skip".to_string(),
+        }
+    }
+
+    fn status() -> Status {
+        Status {
+            phase: "work".to_string(),
+            kind: "work".to_string(),
+            remaining_secs: 750,
+            duration_secs: 1500,
+            running: true,
+            iterations: 1,
+            sessions_completed: 2,
+            overtime_secs: 0,
+            elapsed_secs: 0,
+        }
+    }
+
+    #[test]
+    fn test_render_template() -> crate::error::Result<()> {
+        let text = render_template(
+            "{phase}/{kind} {remaining} {remaining_secs}s {state} {iterations} {sessions} {percentage}%",
+            &status(),
+        );
+        assert_eq!(text, "work/work 12:30 750s running 1 2 50%");
+
+        let overtime = Status {
+            remaining_secs: 0,
+            overtime_secs: 95,
+            ..status()
+        };
+        assert_eq!(render_template("{remaining}", &overtime), "+01:35");
+
+        let flowtime = Status {
+            remaining_secs: 0,
+            duration_secs: 0,
+            elapsed_secs: 2700,
+            ..status()
+        };
+        assert_eq!(render_template("{remaining}", &flowtime), "45:00");
+        Ok(())
+    }
+
+    #[test]
+    fn test_waybar_line() -> crate::error::Result<()> {
+        let args = args(BarFormat::Waybar);
+        let value: serde_json::Value =
+            serde_json::from_str(&format_line(&args, None, Some(&status())))?;
+        assert_eq!(value["text"], "work 12:30");
+        assert_eq!(value["class"], json!(["work", "work", "running"]));
+        assert_eq!(value["percentage"], 50);
+
+        let value: serde_json::Value = serde_json::from_str(&format_line(&args, None, None))?;
+        assert_eq!(value["class"], "disconnected");
+        Ok(())
+    }
+
+    #[test]
+    fn test_i3bar_and_polybar_lines() -> crate::error::Result<()> {
+        let line = format_line(&args(BarFormat::I3bar), None, Some(&status()));
+        assert!(line.starts_with('[') && line.ends_with("],"));
+        assert!(line.contains(r#""full_text":"work 12:30""#));
+
+        let line = format_line(&args(BarFormat::Polybar), None, Some(&status()));
+        assert_eq!(
+            line,
+            "%{A1:verandah-pomodoroctl toggle:}%{A2:verandah-pomodoroctl reset:}%{A3:verandah-pomodoroctl skip:}work 12:30%{A}%{A}%{A}"
+        );
+        let line = format_line(&args(BarFormat::Polybar), Some("key3"), Some(&status()));
+        assert!(line.starts_with("%{A1:verandah-pomodoroctl --instance key3 toggle:}"));
+        assert_eq!(format_line(&args(BarFormat::Polybar), None, None), "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_polybar_parameterised_verbs() -> crate::error::Result<()> {
+        let args = BarArgs {
+            on_click: "extend:5m".to_string(),
+            on_right_click: "phase:long_break".to_string(),
+            ..args(BarFormat::Polybar)
+        };
+        let line = format_line(&args, None, Some(&status()));
+        assert!(line.starts_with("%{A1:verandah-pomodoroctl extend 5m:}"));
+        assert!(line.contains("%{A3:verandah-pomodoroctl phase long_break:}"));
+
+        // Colons left in the command are escaped so they do not end the action
+        let line = format_line(&args, Some("desk:2"), Some(&status()));
+        assert!(line.starts_with(r"%{A1:verandah-pomodoroctl --instance desk\:2 extend 5m:}"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_click_verb() -> crate::error::Result<()> {
+        let args = args(BarFormat::I3bar);
+        assert_eq!(click_verb(&args, "["), None);
+        assert_eq!(
+            click_verb(&args, r#"{"name":"pomodoro","button":1,"x":10}"#),
+            Some("toggle")
+        );
+        assert_eq!(
+            click_verb(&args, r#",{"name":"pomodoro","button":3}"#),
+            Some("skip")
+        );
+        assert_eq!(
+            click_verb(&args, r#",{"name":"pomodoro","button":4}"#),
+            None
+        );
+        Ok(())
+    }
+}
@@ -1,17 +1,60 @@
 use clap::Parser;
-use verandah_plugin_pomodoro::{cli::Cli, socket};
+use verandah_plugin_pomodoro::{
+    bar,
+    cli::{Cli, Commands},
+    socket, stats,
+};
 
 fn main() {
     let cli = Cli::parse();
-    let command = cli.command.as_str();
+    let instance = cli.instance.as_deref();
 
-    match socket::send_command(command) {
-        Ok(()) => {
-            eprintln!("Sent: {command}");
-        }
-        Err(e) => {
-            eprintln!("Error: {e}");
-            std::process::exit(1);
+    let result = match &cli.command {
+        Commands::List => list(),
+        Commands::Bar(args) => bar::run(args, instance),
+        Commands::Stats(args) => stats::run(args),
+        // Everything else is a verb sent to the plugin over the socket
+        Commands::Toggle => send(instance, "toggle"),
+        Commands::Start => send(instance, "start"),
+        Commands::Stop => send(instance, "stop"),
+        Commands::Reset => send(instance, "reset"),
+        Commands::Skip => send(instance, "skip"),
+        Commands::Status => send(instance, "status"),
+        Commands::Extend { duration } => send(instance, &format!("extend:{duration}")),
+        Commands::Shorten { duration } => send(instance, &format!("shorten:{duration}")),
+        Commands::Set { duration } => send(instance, &format!("set:{duration}")),
+        Commands::Phase { name } => send(instance, &format!("phase:{name}")),
+    };
+
+    if let Err(e) = result {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn send(instance: Option<&str>, verb: &str) -> std::io::Result<()> {
+    let reply = socket::send_command(instance, verb)?;
+    print!("{reply}");
+    // Scripts rely on the exit status to tell whether the command was accepted
+    if reply.starts_with("error") {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Print one line per running instance: id, phase, remaining time and state
+fn list() -> std::io::Result<()> {
+    for instance in socket::list_instances() {
+        let id = instance.id.as_deref().unwrap_or("-");
+        match socket::query_status(&instance.socket_path) {
+            Ok(status) => println!(
+                "{id}\t{}\t{}\t{}",
+                status.phase,
+                status.remaining_formatted(),
+                if status.running { "running" } else { "paused" }
+            ),
+            Err(e) => println!("{id}\tunreachable: {e}"),
         }
     }
+    Ok(())
 }
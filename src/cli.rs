@@ -1,10 +1,13 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(name = "verandah-pomodoroctl")]
 #[command(about = "Control the verandah pomodoro timer")]
 #[command(version)]
 pub struct Cli {
+    /// Widget instance to talk to, as shown by `list` (needed when several are running)
+    #[arg(long, short, global = true)]
+    pub instance: Option<String>,
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -21,17 +24,76 @@ pub enum Commands {
     Reset,
     /// Skip to the next phase
     Skip,
+    /// Print the current phase, remaining time and counters
+    Status,
+    /// Lengthen the current phase (e.g. 5m, 90s, 1h30m; a bare number is minutes)
+    Extend { duration: String },
+    /// Shorten the current phase
+    Shorten { duration: String },
+    /// Set the time remaining in the current phase
+    Set { duration: String },
+    /// Jump to the start of a phase by name
+    Phase { name: String },
+    /// List the running widget instances
+    List,
+    /// Follow the timer and print continuous output for a desktop status bar
+    Bar(BarArgs),
+    /// Summarise the session history
+    Stats(StatsArgs),
 }
 
-impl Commands {
-    #[allow(dead_code)]
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            Commands::Toggle => "toggle",
-            Commands::Start => "start",
-            Commands::Stop => "stop",
-            Commands::Reset => "reset",
-            Commands::Skip => "skip",
-        }
-    }
+#[derive(Debug, Clone, clap::Args)]
+pub struct BarArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = BarFormat::Waybar)]
+    pub format: BarFormat,
+    /// Text template; placeholders: {phase} {kind} {remaining} {remaining_secs}
+    /// {state} {iterations} {sessions} {percentage}
+    #[arg(long, default_value = "{phase} {remaining}")]
+    pub template: String,
+    /// Verb sent on left click (i3bar and polybar)
+    #[arg(long, default_value = "toggle")]
+    pub on_click: String,
+    /// Verb sent on middle click (i3bar and polybar)
+    #[arg(long, default_value = "reset")]
+    pub on_middle_click: String,
+    /// Verb sent on right click (i3bar and polybar)
+    #[arg(long, default_value = "skip")]
+    pub on_right_click: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BarFormat {
+    /// One JSON object per line for a waybar custom module (`return-type = "json"`)
+    Waybar,
+    /// i3bar/swaybar protocol, reading click events from stdin
+    I3bar,
+    /// Plain text lines with polybar click action tags
+    Polybar,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct StatsArgs {
+    /// First day to include (YYYY-MM-DD)
+    #[arg(long)]
+    pub since: Option<String>,
+    /// Last day to include (YYYY-MM-DD)
+    #[arg(long)]
+    pub until: Option<String>,
+    /// Group focus time by day, week or month
+    #[arg(long, value_enum, default_value_t = StatsPeriod::Day)]
+    pub by: StatsPeriod,
+    /// Print the report as JSON
+    #[arg(long, conflicts_with = "csv")]
+    pub json: bool,
+    /// Print the per-period table as CSV
+    #[arg(long)]
+    pub csv: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StatsPeriod {
+    Day,
+    Week,
+    Month,
 }
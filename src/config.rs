@@ -1,20 +1,53 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
+use chrono::NaiveTime;
 use derive_more::Debug;
 use serde::Deserialize;
 use strum::VariantNames;
 use verandah_plugin::utils::prelude::*;
 
-use crate::render::{FillDirection, PhaseIndicatorDisplay, RenderMode};
+use crate::hooks::HookEvent;
+use crate::render::{
+    FillDirection, PhaseIndicatorDisplay, RadialDirection, RadialStyle, RenderMode, Role, Slice,
+    TransitionStyle, WarnStyle,
+};
+use crate::schedule::{PhaseKind, PhaseSpec, Schedule};
+use crate::state::RestorePolicy;
+use crate::timer::{FlowtimeBreaks, FlowtimeStep, OvertimePolicy, SuspendPolicy, TimerMode};
 
 pub const DEFAULT_WORK_MINS: u64 = 25;
 pub const DEFAULT_SHORT_BREAK_MINS: u64 = 5;
 pub const DEFAULT_LONG_BREAK_MINS: u64 = 15;
+pub const DEFAULT_LONG_BREAK_AFTER: u8 = 4;
 pub const DEFAULT_INTERVAL_MS: u64 = 1000;
 pub const DEFAULT_PADDING: f32 = 0.05;
 pub const DEFAULT_RENDER_MODE: &str = "text";
+pub const DEFAULT_ROLE: &str = "countdown";
 pub const DEFAULT_FILL_DIRECTION: &str = "empty_to_full";
+pub const DEFAULT_RADIAL_THICKNESS: u32 = 8;
+pub const DEFAULT_RADIAL_DIRECTION: &str = "clockwise";
+/// More keys than any deck has in a row
+pub const MAX_SLICE_COUNT: u32 = 32;
 pub const DEFAULT_PHASE_INDICATOR_DISPLAY: &str = "paused";
+pub const DEFAULT_WARN_STYLE: &str = "blink";
+pub const DEFAULT_TRANSITION: &str = "off";
+pub const DEFAULT_TRANSITION_MS: u64 = 400;
+pub const DEFAULT_RESTORE: &str = "catch_up";
+pub const DEFAULT_ON_SUSPEND: &str = "continue";
+pub const DEFAULT_OVERTIME: &str = "off";
+pub const DEFAULT_MODE: &str = "pomodoro";
+pub const DEFAULT_FLOWTIME_RATIO: f32 = 0.2;
+pub const DEFAULT_DAY_STARTS_AT: &str = "04:00";
+/// [`DEFAULT_DAY_STARTS_AT`] as a time
+const DEFAULT_DAY_START: NaiveTime = match NaiveTime::from_hms_opt(4, 0, 0) {
+    Some(time) => time,
+    None => NaiveTime::MIN,
+};
+pub const DEFAULT_HOOK_TIMEOUT_SECS: u64 = 10;
+pub const DEFAULT_NOTIFY_SUMMARY: &str = "{label}";
+pub const DEFAULT_NOTIFY_WORK_BODY: &str = "{duration} of focus";
+pub const DEFAULT_NOTIFY_BREAK_BODY: &str = "{duration} break";
 
 /// Final configuration after building from TOML
 #[derive(Debug, Clone)]
@@ -25,31 +58,67 @@ pub struct Config {
     pub short_break: u64,
     /// Long break duration in minutes
     pub long_break: u64,
-    /// Auto-start work after break
-    pub auto_start_work: bool,
-    /// Auto-start break after work
-    pub auto_start_break: bool,
+    /// Phases the timer cycles through, either from `[[schedule]]` or the
+    /// classic work/short_break/long_break cycle
+    pub schedule: Schedule,
     /// Polling interval in milliseconds
     pub interval: u64,
     /// Text padding as fraction of button size (0.0 to 0.4)
     pub padding: f32,
     /// Render mode
     pub render_mode: RenderMode,
+    /// What the key shows of its timer
+    pub role: Role,
+    /// Part of the row-wide bar drawn by a `slice` key
+    pub slice: Slice,
     /// Fill direction for fill_bg mode
     pub fill_direction: FillDirection,
+    /// Ring shape for radial mode
+    pub radial: RadialStyle,
     /// When to display the phase indicator
     pub phase_indicator_display: PhaseIndicatorDisplay,
     /// Pulse brightness when paused (for icon-based render modes)
     pub pulse_on_pause: bool,
-    /// Sound files to play on phase transitions (keys: work, short_break, long_break)
-    /// Sound indicates the phase that is STARTING, not the one that ended
-    pub sounds: HashMap<String, String>,
-    /// Phase indicator text (keys: work, short_break, long_break)
-    pub phases: HashMap<String, String>,
-    /// Labels/fallback text (keys: work, short_break, long_break, paused)
+    /// Time left at which a running phase starts warning that it is ending (zero disables)
+    pub warn_before: Duration,
+    /// How the key shows the warning
+    pub warn_style: WarnStyle,
+    /// Sound played once when the warning starts
+    pub warning_sound: Option<String>,
+    /// Animation shown when the key moves on to a new phase
+    pub transition: TransitionStyle,
+    /// How long the phase change animation runs
+    pub transition_length: Duration,
+    /// How to account for downtime when restoring saved timer state
+    pub restore: RestorePolicy,
+    /// What to do with time spent suspended while the timer was running
+    pub on_suspend: SuspendPolicy,
+    /// What to do when a work phase runs out
+    pub overtime: OvertimePolicy,
+    /// Whether work phases count down or up
+    pub mode: TimerMode,
+    /// Break sizing in flowtime mode
+    pub flowtime: FlowtimeBreaks,
+    /// Completed work phases to aim for each day (0 disables the goal)
+    pub daily_goal: u32,
+    /// Local time at which the daily goal starts counting again
+    pub day_starts_at: NaiveTime,
+    /// Shell commands to run on timer events
+    pub hooks: HashMap<HookEvent, String>,
+    /// Seconds a hook command may run before it is killed
+    pub hook_timeout: u64,
+    /// Post a desktop notification when a new phase comes up
+    pub notify: bool,
+    /// Labels/fallback text (keys: phase names, paused)
     pub labels: HashMap<String, String>,
     /// Colors (keys: fg, work_bg, break_bg, paused_bg, empty_bg) - format: #RRGGBB or #RGB
     pub colors: HashMap<String, String>,
+    /// Background color stops over a phase (keys: phase names, work, break)
+    pub gradients: HashMap<String, Vec<String>>,
+    /// Id of this widget instance, naming its socket and state file
+    pub instance: Option<String>,
+    /// Name of a timer shared with other widgets (None: a timer of its own)
+    pub timer: Option<String>,
 }
 
 /// Builder for Config that deserializes from TOML and applies defaults
@@ -59,14 +128,45 @@ pub struct ConfigBuilder {
     work: u64,
     short_break: u64,
     long_break: u64,
+    long_break_after: u8,
     auto_start_work: bool,
     auto_start_break: bool,
     interval: u64,
     padding: f32,
     render_mode: String,
+    role: String,
+    slice_index: u32,
+    slice_count: u32,
+    timer: Option<String>,
     fill_direction: String,
+    radial_thickness: u32,
+    radial_start_angle: f32,
+    radial_direction: String,
+    radial_antialias: bool,
     phase_indicator_display: String,
     pulse_on_pause: bool,
+    warn_before: u64,
+    warn_style: String,
+    warning_sound: Option<String>,
+    transition: String,
+    transition_ms: u64,
+    restore: String,
+    on_suspend: String,
+    overtime: String,
+    mode: String,
+    flowtime_ratio: f32,
+    #[serde(default)]
+    flowtime_breaks: Vec<FlowtimeStepBuilder>,
+    daily_goal: u32,
+    day_starts_at: String,
+    hook_timeout: u64,
+    notify: bool,
+    #[serde(default)]
+    hooks: HashMap<String, String>,
+    #[serde(default)]
+    notify_summary: HashMap<String, String>,
+    #[serde(default)]
+    notify_body: HashMap<String, String>,
     #[serde(default)]
     sounds: HashMap<String, String>,
     #[serde(default)]
@@ -75,6 +175,13 @@ pub struct ConfigBuilder {
     labels: HashMap<String, String>,
     #[serde(default, alias = "colours")]
     colors: HashMap<String, String>,
+    #[serde(default)]
+    gradients: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    schedule: Vec<PhaseSpecBuilder>,
+    /// Widget id added by verandah
+    #[serde(rename = "_widget_id")]
+    widget_id: Option<WidgetId>,
     /// Catch-all for unknown fields (logged as warnings in build())
     #[serde(flatten)]
     #[debug(skip)]
@@ -87,23 +194,99 @@ impl Default for ConfigBuilder {
             work: DEFAULT_WORK_MINS,
             short_break: DEFAULT_SHORT_BREAK_MINS,
             long_break: DEFAULT_LONG_BREAK_MINS,
+            long_break_after: DEFAULT_LONG_BREAK_AFTER,
             auto_start_work: false,
             auto_start_break: false,
             interval: DEFAULT_INTERVAL_MS,
             padding: DEFAULT_PADDING,
             render_mode: DEFAULT_RENDER_MODE.to_string(),
+            role: DEFAULT_ROLE.to_string(),
+            slice_index: 0,
+            slice_count: 1,
+            timer: None,
             fill_direction: DEFAULT_FILL_DIRECTION.to_string(),
+            radial_thickness: DEFAULT_RADIAL_THICKNESS,
+            radial_start_angle: 0.0,
+            radial_direction: DEFAULT_RADIAL_DIRECTION.to_string(),
+            radial_antialias: true,
             phase_indicator_display: DEFAULT_PHASE_INDICATOR_DISPLAY.to_string(),
             pulse_on_pause: false,
+            warn_before: 0,
+            warn_style: DEFAULT_WARN_STYLE.to_string(),
+            warning_sound: None,
+            transition: DEFAULT_TRANSITION.to_string(),
+            transition_ms: DEFAULT_TRANSITION_MS,
+            restore: DEFAULT_RESTORE.to_string(),
+            on_suspend: DEFAULT_ON_SUSPEND.to_string(),
+            overtime: DEFAULT_OVERTIME.to_string(),
+            mode: DEFAULT_MODE.to_string(),
+            flowtime_ratio: DEFAULT_FLOWTIME_RATIO,
+            flowtime_breaks: Vec::new(),
+            daily_goal: 0,
+            day_starts_at: DEFAULT_DAY_STARTS_AT.to_string(),
+            hook_timeout: DEFAULT_HOOK_TIMEOUT_SECS,
+            notify: false,
+            hooks: HashMap::new(),
+            notify_summary: HashMap::new(),
+            notify_body: HashMap::new(),
             sounds: HashMap::new(),
             phases: HashMap::new(),
             labels: HashMap::new(),
             colors: HashMap::new(),
+            gradients: HashMap::new(),
+            schedule: Vec::new(),
+            widget_id: None,
             unknown: HashMap::new(),
         }
     }
 }
 
+/// One `[[schedule]]` entry as written in TOML
+#[derive(Debug, Deserialize)]
+struct PhaseSpecBuilder {
+    name: String,
+    /// Duration in minutes
+    duration: u64,
+    #[serde(default)]
+    kind: PhaseKind,
+    auto_start: Option<bool>,
+    icon: Option<String>,
+    sound: Option<String>,
+    indicator: Option<String>,
+    label: Option<String>,
+    notify_summary: Option<String>,
+    notify_body: Option<String>,
+    #[serde(default, alias = "colours")]
+    colors: HashMap<String, String>,
+}
+
+/// The `_widget_id` verandah adds to each widget's config
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum WidgetId {
+    Name(String),
+    Number(i64),
+}
+
+impl WidgetId {
+    fn into_string(self) -> String {
+        match self {
+            WidgetId::Name(name) => name,
+            WidgetId::Number(number) => number.to_string(),
+        }
+    }
+}
+
+/// One `flowtime_breaks` row as written in TOML
+#[derive(Debug, Deserialize)]
+struct FlowtimeStepBuilder {
+    /// Work shorter than this many minutes (unset: any length)
+    under: Option<u64>,
+    /// Break length in minutes
+    #[serde(rename = "break")]
+    break_mins: u64,
+}
+
 impl ConfigBuilder {
     fn default_colors() -> HashMap<String, String> {
         let mut colors = HashMap::new();
@@ -114,6 +297,8 @@ impl ConfigBuilder {
         colors.insert("empty_bg".to_string(), "#2c3e50".to_string()); // Dark blue-gray
         colors.insert("dot_running".to_string(), "#008000".to_string()); // Dark green
         colors.insert("dot_paused".to_string(), "#808080".to_string()); // Gray
+        colors.insert("goal".to_string(), "#f1c40f".to_string()); // Gold
+        colors.insert("overtime".to_string(), "#f39c12".to_string()); // Orange
         colors
     }
 
@@ -123,14 +308,102 @@ impl ConfigBuilder {
         labels
     }
 
+    /// Build the classic cycle: `long_break_after` work phases separated by
+    /// short breaks, the last one followed by a long break
+    ///
+    /// Returns the opening work phase and the phases after it.
+    fn classic_schedule(&self) -> (PhaseSpecBuilder, Vec<PhaseSpecBuilder>) {
+        let phase = |name: &str, duration: u64, kind: PhaseKind| PhaseSpecBuilder {
+            name: name.to_string(),
+            duration,
+            kind,
+            auto_start: None,
+            icon: None,
+            sound: None,
+            indicator: None,
+            label: None,
+            notify_summary: None,
+            notify_body: None,
+            colors: HashMap::new(),
+        };
+
+        // The cycle always opens with work; the rest follows it
+        let first = phase("work", self.work, PhaseKind::Work);
+        let mut rest = Vec::new();
+        for i in 1..=self.long_break_after {
+            if i > 1 {
+                rest.push(phase("work", self.work, PhaseKind::Work));
+            }
+            if i < self.long_break_after {
+                rest.push(phase("short_break", self.short_break, PhaseKind::Break));
+            } else {
+                rest.push(phase("long_break", self.long_break, PhaseKind::Break));
+            }
+        }
+        (first, rest)
+    }
+
+    /// Resolve a schedule entry, filling unset fields from the top-level
+    /// `sounds`, `phases`, `labels`, `notify_*` and `auto_start_*` settings
+    fn build_phase(&self, builder: PhaseSpecBuilder) -> PhaseSpec {
+        let name = builder.name;
+
+        let duration = if builder.duration == 0 {
+            tracing::warn!(phase = name, "phase duration is 0, clamping to 1 minute");
+            1
+        } else {
+            builder.duration
+        };
+
+        let auto_start = builder.auto_start.unwrap_or(match builder.kind {
+            PhaseKind::Work => self.auto_start_work,
+            PhaseKind::Break => self.auto_start_break,
+        });
+
+        let default_notify_body = match builder.kind {
+            PhaseKind::Work => DEFAULT_NOTIFY_WORK_BODY,
+            PhaseKind::Break => DEFAULT_NOTIFY_BREAK_BODY,
+        };
+
+        let (default_indicator, default_label) = match name.as_str() {
+            "work" => ("work", "Work"),
+            "short_break" => ("short brk", "Short\nBreak"),
+            "long_break" => ("long brk", "Long\nBreak"),
+            other => (other, other),
+        };
+
+        PhaseSpec {
+            kind: builder.kind,
+            duration_secs: duration * 60,
+            auto_start,
+            icon: builder.icon.unwrap_or_else(|| name.clone()),
+            sound: builder.sound.or_else(|| self.sounds.get(&name).cloned()),
+            indicator: builder
+                .indicator
+                .or_else(|| self.phases.get(&name).cloned())
+                .unwrap_or_else(|| default_indicator.to_string()),
+            label: builder
+                .label
+                .or_else(|| self.labels.get(&name).cloned())
+                .unwrap_or_else(|| default_label.to_string()),
+            notify_summary: builder
+                .notify_summary
+                .or_else(|| self.notify_summary.get(&name).cloned())
+                .unwrap_or_else(|| DEFAULT_NOTIFY_SUMMARY.to_string()),
+            notify_body: builder
+                .notify_body
+                .or_else(|| self.notify_body.get(&name).cloned())
+                .unwrap_or_else(|| default_notify_body.to_string()),
+            colors: builder.colors,
+            name,
+        }
+    }
+
     /// Build the final Config, logging warnings for unknown fields
     /// and merging defaults for colors/labels.
     pub fn build(mut self) -> Config {
-        // Log warnings for unknown fields (skip internal fields added by verandah)
+        // Log warnings for unknown fields
         for key in self.unknown.keys() {
-            if key == "_widget_id" {
-                continue;
-            }
             tracing::warn!(field = key, "Unknown config field");
         }
 
@@ -147,6 +420,26 @@ impl ConfigBuilder {
             tracing::warn!("long_break duration is 0, clamping to 1 minute");
             self.long_break = 1;
         }
+        if self.long_break_after == 0 {
+            tracing::warn!("long_break_after is 0, clamping to 1 work session");
+            self.long_break_after = 1;
+        }
+        if self.hook_timeout == 0 {
+            tracing::warn!("hook_timeout is 0, clamping to 1 second");
+            self.hook_timeout = 1;
+        }
+
+        let mut configured = std::mem::take(&mut self.schedule).into_iter();
+        let (first, rest) = match configured.next() {
+            Some(first) => (first, configured.collect()),
+            None => self.classic_schedule(),
+        };
+        let schedule = Schedule::new(
+            self.build_phase(first),
+            rest.into_iter()
+                .map(|builder| self.build_phase(builder))
+                .collect(),
+        );
 
         // Merge defaults for colors
         for (key, value) in Self::default_colors() {
@@ -167,6 +460,39 @@ impl ConfigBuilder {
             RenderMode::default()
         });
 
+        let role: Role = self.role.parse().unwrap_or_else(|_| {
+            tracing::warn!(
+                value = self.role,
+                valid = ?Role::VARIANTS,
+                "Unknown role, using default"
+            );
+            Role::default()
+        });
+
+        if self.slice_count == 0 {
+            tracing::warn!("slice_count is 0, clamping to 1");
+            self.slice_count = 1;
+        } else if self.slice_count > MAX_SLICE_COUNT {
+            tracing::warn!(
+                slice_count = self.slice_count,
+                max = MAX_SLICE_COUNT,
+                "slice_count is too large, clamping"
+            );
+            self.slice_count = MAX_SLICE_COUNT;
+        }
+        if self.slice_index >= self.slice_count {
+            tracing::warn!(
+                slice_index = self.slice_index,
+                slice_count = self.slice_count,
+                "slice_index is past the last slice, using the last one"
+            );
+            self.slice_index = self.slice_count - 1;
+        }
+        let slice = Slice {
+            index: self.slice_index,
+            count: self.slice_count,
+        };
+
         let fill_direction: FillDirection = self.fill_direction.parse().unwrap_or_else(|_| {
             tracing::warn!(
                 value = self.fill_direction,
@@ -176,6 +502,29 @@ impl ConfigBuilder {
             FillDirection::default()
         });
 
+        let radial_direction: RadialDirection =
+            self.radial_direction.parse().unwrap_or_else(|_| {
+                tracing::warn!(
+                    value = self.radial_direction,
+                    valid = ?RadialDirection::VARIANTS,
+                    "Unknown radial_direction, using default"
+                );
+                RadialDirection::default()
+            });
+        if !self.radial_start_angle.is_finite() {
+            tracing::warn!(
+                value = self.radial_start_angle,
+                "radial_start_angle must be a number of degrees, using 0"
+            );
+            self.radial_start_angle = 0.0;
+        }
+        let radial = RadialStyle {
+            thickness: self.radial_thickness,
+            start_angle: self.radial_start_angle.rem_euclid(360.0),
+            direction: radial_direction,
+            antialias: self.radial_antialias,
+        };
+
         let phase_indicator_display: PhaseIndicatorDisplay =
             self.phase_indicator_display.parse().unwrap_or_else(|_| {
                 tracing::warn!(
@@ -186,22 +535,142 @@ impl ConfigBuilder {
                 PhaseIndicatorDisplay::default()
             });
 
+        let warn_style: WarnStyle = self.warn_style.parse().unwrap_or_else(|_| {
+            tracing::warn!(
+                value = self.warn_style,
+                valid = ?WarnStyle::VARIANTS,
+                "Unknown warn_style, using default"
+            );
+            WarnStyle::default()
+        });
+
+        let transition: TransitionStyle = self.transition.parse().unwrap_or_else(|_| {
+            tracing::warn!(
+                value = self.transition,
+                valid = ?TransitionStyle::VARIANTS,
+                "Unknown transition, using default"
+            );
+            TransitionStyle::default()
+        });
+        if self.transition_ms == 0 {
+            tracing::warn!("transition_ms is 0, clamping to 1 millisecond");
+            self.transition_ms = 1;
+        }
+
+        let restore: RestorePolicy = self.restore.parse().unwrap_or_else(|_| {
+            tracing::warn!(
+                value = self.restore,
+                valid = ?RestorePolicy::VARIANTS,
+                "Unknown restore policy, using default"
+            );
+            RestorePolicy::default()
+        });
+
+        let on_suspend: SuspendPolicy = self.on_suspend.parse().unwrap_or_else(|_| {
+            tracing::warn!(
+                value = self.on_suspend,
+                valid = ?SuspendPolicy::VARIANTS,
+                "Unknown on_suspend policy, using default"
+            );
+            SuspendPolicy::default()
+        });
+
+        let mode: TimerMode = self.mode.parse().unwrap_or_else(|_| {
+            tracing::warn!(
+                value = self.mode,
+                valid = ?TimerMode::VARIANTS,
+                "Unknown mode, using default"
+            );
+            TimerMode::default()
+        });
+
+        if !(self.flowtime_ratio > 0.0 && self.flowtime_ratio.is_finite()) {
+            tracing::warn!(
+                value = self.flowtime_ratio,
+                "flowtime_ratio must be positive, using default"
+            );
+            self.flowtime_ratio = DEFAULT_FLOWTIME_RATIO;
+        }
+        let flowtime = FlowtimeBreaks {
+            steps: self
+                .flowtime_breaks
+                .iter()
+                .map(|step| FlowtimeStep {
+                    under: step.under.map(|mins| Duration::from_secs(mins * 60)),
+                    break_length: Duration::from_secs(step.break_mins * 60),
+                })
+                .collect(),
+            ratio: self.flowtime_ratio,
+        };
+
+        let overtime: OvertimePolicy = self.overtime.parse().unwrap_or_else(|_| {
+            tracing::warn!(
+                value = self.overtime,
+                valid = ?OvertimePolicy::VARIANTS,
+                "Unknown overtime policy, using default"
+            );
+            OvertimePolicy::default()
+        });
+
+        let day_starts_at =
+            NaiveTime::parse_from_str(&self.day_starts_at, "%H:%M").unwrap_or_else(|_| {
+                tracing::warn!(
+                    value = self.day_starts_at,
+                    "Invalid day_starts_at (expected HH:MM), using default"
+                );
+                DEFAULT_DAY_START
+            });
+
+        let hooks = self
+            .hooks
+            .iter()
+            .filter_map(|(key, command)| match key.parse::<HookEvent>() {
+                Ok(event) => Some((event, command.clone())),
+                Err(_) => {
+                    tracing::warn!(
+                        hook = key,
+                        valid = ?HookEvent::VARIANTS,
+                        "Unknown hook, ignoring"
+                    );
+                    None
+                }
+            })
+            .collect();
+
         Config {
             work: self.work,
             short_break: self.short_break,
             long_break: self.long_break,
-            auto_start_work: self.auto_start_work,
-            auto_start_break: self.auto_start_break,
+            schedule,
             interval: self.interval,
             padding: self.padding,
             render_mode,
+            role,
+            slice,
             fill_direction,
+            radial,
             phase_indicator_display,
             pulse_on_pause: self.pulse_on_pause,
-            sounds: self.sounds,
-            phases: self.phases,
+            warn_before: Duration::from_secs(self.warn_before),
+            warn_style,
+            warning_sound: self.warning_sound.filter(|name| !name.is_empty()),
+            transition,
+            transition_length: Duration::from_millis(self.transition_ms),
+            restore,
+            on_suspend,
+            overtime,
+            mode,
+            flowtime,
+            daily_goal: self.daily_goal,
+            day_starts_at,
+            hooks,
+            hook_timeout: self.hook_timeout,
+            notify: self.notify,
             labels: self.labels,
             colors: self.colors,
+            gradients: self.gradients,
+            instance: self.widget_id.map(WidgetId::into_string),
+            timer: self.timer.filter(|name| !name.is_empty()),
         }
     }
 }
@@ -216,13 +685,89 @@ mod tests {
         assert_eq!(cfg.work, 25);
         assert_eq!(cfg.short_break, 5);
         assert_eq!(cfg.long_break, 15);
-        assert!(!cfg.auto_start_work);
-        assert!(!cfg.auto_start_break);
+        assert_eq!(cfg.schedule.work_count(), 4);
+        assert!(cfg.schedule.phases().iter().all(|p| !p.auto_start));
+        assert_eq!(cfg.restore, RestorePolicy::CatchUp);
+        assert_eq!(cfg.on_suspend, SuspendPolicy::Continue);
+        assert_eq!(cfg.overtime, OvertimePolicy::Off);
+        assert_eq!(cfg.mode, TimerMode::Pomodoro);
         assert_eq!(cfg.colors.get("fg"), Some(&"#ffffff".to_string()));
         assert_eq!(cfg.colors.get("work_bg"), Some(&"#e57373".to_string()));
         assert_eq!(cfg.colors.get("break_bg"), Some(&"#81c784".to_string()));
         assert_eq!(cfg.colors.get("paused_bg"), Some(&"#7f8c8d".to_string()));
         assert_eq!(cfg.colors.get("empty_bg"), Some(&"#2c3e50".to_string()));
+        assert_eq!(cfg.daily_goal, 0);
+        assert_eq!(
+            cfg.day_starts_at,
+            NaiveTime::from_hms_opt(4, 0, 0).unwrap_or_default()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_daily_goal() -> crate::error::Result<()> {
+        let toml_str = r#"
+daily_goal = 8
+day_starts_at = "05:30"
+"#;
+        let cfg: Config = verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        assert_eq!(cfg.daily_goal, 8);
+        assert_eq!(
+            cfg.day_starts_at,
+            NaiveTime::from_hms_opt(5, 30, 0).unwrap_or_default()
+        );
+
+        // An unparseable boundary falls back to the default
+        let cfg: Config =
+            verandah_plugin::api::toml::from_str::<ConfigBuilder>(r#"day_starts_at = "late""#)?
+                .build();
+        assert_eq!(
+            cfg.day_starts_at,
+            NaiveTime::from_hms_opt(4, 0, 0).unwrap_or_default()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_flowtime() -> crate::error::Result<()> {
+        let toml_str = r#"
+mode = "flowtime"
+flowtime_ratio = -1.0
+flowtime_breaks = [
+    { under = 25, break = 5 },
+    { under = 50, break = 8 },
+    { break = 15 },
+]
+"#;
+        let cfg: Config = verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        assert_eq!(cfg.mode, TimerMode::Flowtime);
+        assert_eq!(cfg.flowtime.ratio, DEFAULT_FLOWTIME_RATIO);
+        assert_eq!(cfg.flowtime.steps.len(), 3);
+        assert_eq!(
+            cfg.flowtime.steps[1].under,
+            Some(Duration::from_secs(50 * 60))
+        );
+        assert_eq!(cfg.flowtime.steps[2].under, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_hooks() -> crate::error::Result<()> {
+        let toml_str = r#"
+hook_timeout = 3
+
+[hooks]
+on_work_start = "makoctl mode -a dnd"
+on_pause = "makoctl mode -r dnd"
+on_lunch = "true"
+"#;
+        let cfg: Config = verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        assert_eq!(cfg.hook_timeout, 3);
+        assert_eq!(cfg.hooks.len(), 2);
+        assert_eq!(
+            cfg.hooks.get(&HookEvent::OnWorkStart).map(String::as_str),
+            Some("makoctl mode -a dnd")
+        );
         Ok(())
     }
 
@@ -243,12 +788,14 @@ work = "bell.wav"
         let cfg: Config = verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
         assert_eq!(cfg.work, 30);
         assert_eq!(cfg.short_break, 10);
-        assert!(cfg.auto_start_work);
+        assert_eq!(cfg.schedule.get(0).duration_secs, 30 * 60);
+        assert!(cfg.schedule.get(0).auto_start);
+        assert!(!cfg.schedule.get(1).auto_start);
         assert_eq!(cfg.colors.get("fg"), Some(&"#000000".to_string()));
         assert_eq!(cfg.colors.get("work_bg"), Some(&"#ff0000".to_string()));
         // defaults should still be present for unspecified fields
         assert_eq!(cfg.long_break, DEFAULT_LONG_BREAK_MINS);
-        assert_eq!(cfg.sounds.get("work"), Some(&"bell.wav".to_string()));
+        assert_eq!(cfg.schedule.get(0).sound, Some("bell.wav".to_string()));
         Ok(())
     }
 
@@ -310,4 +857,240 @@ another_unknown = 42
         assert_eq!(builder.work, 25);
         Ok(())
     }
+
+    #[test]
+    fn test_config_widget_id() -> crate::error::Result<()> {
+        let cfg = ConfigBuilder::default().build();
+        assert_eq!(cfg.instance, None);
+
+        let builder: ConfigBuilder =
+            verandah_plugin::api::toml::from_str(r#"_widget_id = "deck1-key3""#)?;
+        assert!(!builder.unknown.contains_key("_widget_id"));
+        assert_eq!(builder.build().instance.as_deref(), Some("deck1-key3"));
+
+        let cfg: Config =
+            verandah_plugin::api::toml::from_str::<ConfigBuilder>("_widget_id = 7")?.build();
+        assert_eq!(cfg.instance.as_deref(), Some("7"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_shared_timer_role() -> crate::error::Result<()> {
+        let cfg = ConfigBuilder::default().build();
+        assert_eq!((cfg.timer, cfg.role), (None, Role::Countdown));
+
+        let toml_str = r#"
+timer = "desk"
+role = "skip"
+"#;
+        let cfg: Config = verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        assert_eq!(cfg.timer.as_deref(), Some("desk"));
+        assert_eq!(cfg.role, Role::Skip);
+
+        let cfg: Config =
+            verandah_plugin::api::toml::from_str::<ConfigBuilder>(r#"role = "clock""#)?.build();
+        assert_eq!(cfg.role, Role::Countdown);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_slice() -> crate::error::Result<()> {
+        assert_eq!(ConfigBuilder::default().build().slice, Slice::default());
+
+        let toml_str = r#"
+role = "slice"
+slice_index = 2
+slice_count = 5
+"#;
+        let cfg: Config = verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        assert_eq!(cfg.role, Role::Slice);
+        assert_eq!(cfg.slice, Slice { index: 2, count: 5 });
+
+        let toml_str = r#"
+slice_index = 4
+slice_count = 0
+"#;
+        let cfg: Config = verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        assert_eq!(cfg.slice, Slice { index: 0, count: 1 });
+
+        let toml_str = r#"
+slice_index = 3
+slice_count = 4000000000
+"#;
+        let cfg: Config = verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        assert_eq!(
+            cfg.slice,
+            Slice {
+                index: 3,
+                count: MAX_SLICE_COUNT
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_radial() -> crate::error::Result<()> {
+        assert_eq!(
+            ConfigBuilder::default().build().radial,
+            RadialStyle::default()
+        );
+
+        let toml_str = r#"
+render_mode = "radial"
+radial_thickness = 0
+radial_start_angle = -90.0
+radial_direction = "counterclockwise"
+radial_antialias = false
+"#;
+        let cfg: Config = verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        assert_eq!(cfg.render_mode, RenderMode::Radial);
+        assert_eq!(
+            cfg.radial,
+            RadialStyle {
+                thickness: 0,
+                start_angle: 270.0,
+                direction: RadialDirection::Counterclockwise,
+                antialias: false,
+            }
+        );
+
+        let cfg: Config =
+            verandah_plugin::api::toml::from_str::<ConfigBuilder>("radial_direction = \"up\"")?
+                .build();
+        assert_eq!(cfg.radial.direction, RadialDirection::Clockwise);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_warning() -> crate::error::Result<()> {
+        let cfg = ConfigBuilder::default().build();
+        assert_eq!(cfg.warn_before, Duration::ZERO);
+        assert_eq!(cfg.warn_style, WarnStyle::Blink);
+        assert_eq!(cfg.warning_sound, None);
+
+        let toml_str = r#"
+warn_before = 60
+warn_style = "flash"
+warning_sound = "bell"
+"#;
+        let cfg: Config = verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        assert_eq!(cfg.warn_before, Duration::from_secs(60));
+        assert_eq!(cfg.warn_style, WarnStyle::Flash);
+        assert_eq!(cfg.warning_sound.as_deref(), Some("bell"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_transition() -> crate::error::Result<()> {
+        let cfg = ConfigBuilder::default().build();
+        assert_eq!(cfg.transition, TransitionStyle::Off);
+        assert_eq!(
+            cfg.transition_length,
+            Duration::from_millis(DEFAULT_TRANSITION_MS)
+        );
+
+        let toml_str = r#"
+transition = "crossfade"
+transition_ms = 250
+"#;
+        let cfg: Config = verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        assert_eq!(cfg.transition, TransitionStyle::Crossfade);
+        assert_eq!(cfg.transition_length, Duration::from_millis(250));
+
+        let cfg: Config =
+            verandah_plugin::api::toml::from_str::<ConfigBuilder>("transition = \"spin\"")?.build();
+        assert_eq!(cfg.transition, TransitionStyle::Off);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_long_break_after_zero_clamped() -> crate::error::Result<()> {
+        let cfg: Config =
+            verandah_plugin::api::toml::from_str::<ConfigBuilder>("long_break_after = 0")?.build();
+        assert_eq!(cfg.schedule.work_count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_classic_schedule() -> crate::error::Result<()> {
+        let cfg: Config =
+            verandah_plugin::api::toml::from_str::<ConfigBuilder>("long_break_after = 3")?.build();
+        let names: Vec<&str> = cfg
+            .schedule
+            .phases()
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "work",
+                "short_break",
+                "work",
+                "short_break",
+                "work",
+                "long_break"
+            ]
+        );
+        assert_eq!(cfg.schedule.get(1).indicator, "short brk");
+        assert_eq!(cfg.schedule.get(5).label, "Long\nBreak");
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_custom_schedule() -> crate::error::Result<()> {
+        let toml_str = r##"
+auto_start_break = true
+
+[sounds]
+review = "complete"
+
+[notify_summary]
+review = "Time to review"
+
+[[schedule]]
+name = "warm-up"
+duration = 5
+auto_start = true
+
+[[schedule]]
+name = "work"
+duration = 50
+
+[[schedule]]
+name = "break"
+kind = "break"
+duration = 10
+icon = "coffee"
+colors = { bg = "#00ff00" }
+
+[[schedule]]
+name = "review"
+duration = 15
+label = "Review"
+"##;
+        let cfg: Config = verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        assert_eq!(cfg.schedule.phases().len(), 4);
+        assert_eq!(cfg.schedule.work_count(), 3);
+
+        let warm_up = cfg.schedule.get(0);
+        assert_eq!(warm_up.duration_secs, 5 * 60);
+        assert!(warm_up.auto_start);
+        assert_eq!(warm_up.indicator, "warm-up");
+
+        let pause = cfg.schedule.get(2);
+        assert!(pause.is_break());
+        // Breaks inherit auto_start_break unless set per phase
+        assert!(pause.auto_start);
+        assert_eq!(pause.icon, "coffee");
+        assert_eq!(pause.colors.get("bg"), Some(&"#00ff00".to_string()));
+
+        assert_eq!(pause.notify_body, DEFAULT_NOTIFY_BREAK_BODY);
+
+        let review = cfg.schedule.get(3);
+        assert_eq!(review.label, "Review");
+        assert_eq!(review.notify_summary, "Time to review");
+        assert_eq!(review.sound, Some("complete".to_string()));
+        Ok(())
+    }
 }
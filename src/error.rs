@@ -11,6 +11,9 @@ pub enum Error {
 
     #[from]
     Toml(verandah_plugin::api::toml::de::Error),
+
+    #[from]
+    Json(serde_json::Error),
 }
 
 impl std::fmt::Display for Error {
@@ -18,6 +21,7 @@ impl std::fmt::Display for Error {
         match self {
             Error::Io(e) => write!(f, "IO error: {e}"),
             Error::Toml(e) => write!(f, "TOML error: {e}"),
+            Error::Json(e) => write!(f, "JSON error: {e}"),
         }
     }
 }
@@ -27,6 +31,7 @@ impl std::error::Error for Error {
         match self {
             Error::Io(e) => Some(e),
             Error::Toml(e) => Some(e),
+            Error::Json(e) => Some(e),
         }
     }
 }
@@ -0,0 +1,96 @@
+//! Background colors that shift as a phase runs out
+//!
+//! A gradient is a list of color stops spread evenly over the phase, from the
+//! first at its start to the last at its end.
+
+use std::collections::HashMap;
+
+use verandah_plugin::utils::prelude::*;
+
+/// Evenly spaced color stops evaluated against a phase's progress
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    stops: Vec<Rgba<u8>>,
+}
+
+impl Gradient {
+    /// A gradient through `stops`; None without any
+    pub fn new(stops: Vec<Rgba<u8>>) -> Option<Self> {
+        (!stops.is_empty()).then_some(Gradient { stops })
+    }
+
+    /// Parse the color strings in `stops`, dropping any that are not colors
+    pub fn parse(stops: &[String]) -> Option<Self> {
+        // parse_colors works on named colors, so name each stop by its position
+        let named: HashMap<String, String> = stops
+            .iter()
+            .enumerate()
+            .map(|(i, stop)| (i.to_string(), stop.clone()))
+            .collect();
+        let parsed = parse_colors(&named);
+        Self::new(
+            (0..stops.len())
+                .filter_map(|i| parsed.get(&i.to_string()).copied())
+                .collect(),
+        )
+    }
+
+    /// Color at `progress` through the phase (0.0 to 1.0)
+    pub fn at(&self, progress: f32) -> Rgba<u8> {
+        let last = self.stops.len() - 1;
+        if last == 0 {
+            return self.stops[0];
+        }
+        let position = progress.clamp(0.0, 1.0) * last as f32;
+        let index = (position.floor() as usize).min(last - 1);
+        mix(
+            self.stops[index],
+            self.stops[index + 1],
+            position - index as f32,
+        )
+    }
+}
+
+/// Blend from `from` to `to` by `t` (0.0 to 1.0)
+pub fn mix(from: Rgba<u8>, to: Rgba<u8>, t: f32) -> Rgba<u8> {
+    Rgba([
+        mix_channel(from[0], to[0], t),
+        mix_channel(from[1], to[1], t),
+        mix_channel(from[2], to[2], t),
+        255,
+    ])
+}
+
+/// Blend one color channel from `from` to `to` by `t` (0.0 to 1.0)
+pub fn mix_channel(from: u8, to: u8, t: f32) -> u8 {
+    (from as f32 + (to as f32 - from as f32) * t).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const GREEN: Rgba<u8> = Rgba([0, 255, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+
+    #[test]
+    fn test_gradient_stops() -> crate::error::Result<()> {
+        assert_eq!(Gradient::new(Vec::new()), None);
+
+        let Some(flat) = Gradient::new(vec![RED]) else {
+            panic!("one stop should make a gradient");
+        };
+        assert_eq!(flat.at(0.7), RED);
+
+        let Some(gradient) = Gradient::new(vec![RED, GREEN, BLUE]) else {
+            panic!("three stops should make a gradient");
+        };
+        assert_eq!(gradient.at(0.0), RED);
+        assert_eq!(gradient.at(0.25), Rgba([128, 128, 0, 255]));
+        assert_eq!(gradient.at(0.5), GREEN);
+        assert_eq!(gradient.at(1.0), BLUE);
+        assert_eq!(gradient.at(1.5), BLUE);
+        Ok(())
+    }
+}
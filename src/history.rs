@@ -0,0 +1,167 @@
+//! Append-only log of finished phases, one JSON object per line
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use xdg::BaseDirectories;
+
+use crate::schedule::PhaseKind;
+
+const HISTORY_DIR: &str = "verandah-plugin-pomodoro";
+const HISTORY_NAME: &str = "history.jsonl";
+
+/// How a phase ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    /// The phase ran for its full length
+    Completed,
+    /// The phase was cut short by `skip` or by jumping to another phase
+    Skipped,
+    /// The timer was reset during the phase
+    Reset,
+    /// The saved phase was discarded when the plugin restarted
+    Interrupted,
+}
+
+/// A phase that was started and has ended
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub phase: String,
+    pub kind: PhaseKind,
+    /// When the phase was first started
+    pub started_at: DateTime<Local>,
+    pub ended_at: DateTime<Local>,
+    /// Length of the phase, including any extend/shorten/set adjustment
+    pub planned_secs: u64,
+    /// Time actually run up to the planned length, excluding pauses
+    pub actual_secs: u64,
+    /// Time run past the planned length with `overtime` enabled
+    #[serde(default)]
+    pub overtime_secs: u64,
+    pub outcome: Outcome,
+    /// Number of times the phase was paused
+    pub interruptions: u32,
+}
+
+/// Get the history file path using the XDG data directory
+pub fn get_history_path() -> Option<PathBuf> {
+    let xdg = BaseDirectories::with_prefix(HISTORY_DIR);
+    xdg.place_data_file(HISTORY_NAME).ok()
+}
+
+/// Append entries to the history file, creating it if needed
+pub fn append<P>(path: P, entries: &[Entry]) -> std::io::Result<()>
+where
+    P: AsRef<Path>,
+{
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let mut lines = String::new();
+    for entry in entries {
+        lines.push_str(&serde_json::to_string(entry).map_err(std::io::Error::other)?);
+        lines.push('\n');
+    }
+
+    // A single write keeps concurrent appends from interleaving mid-line
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(lines.as_bytes())
+}
+
+/// Read every entry from the history file
+///
+/// A missing file is an empty history; unreadable lines are skipped with a warning.
+pub fn load<P>(path: P) -> std::io::Result<Vec<Entry>>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let entries = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(number, line)| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                tracing::warn!(
+                    path = %path.display(),
+                    line = number + 1,
+                    error = %e,
+                    "Skipping unreadable history entry"
+                );
+                None
+            }
+        })
+        .collect();
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(phase: &str, outcome: Outcome) -> Entry {
+        let now = Local::now();
+        Entry {
+            phase: phase.to_string(),
+            kind: PhaseKind::Work,
+            started_at: now - chrono::Duration::minutes(25),
+            ended_at: now,
+            planned_secs: 1500,
+            actual_secs: 1500,
+            overtime_secs: 0,
+            outcome,
+            interruptions: 1,
+        }
+    }
+
+    #[test]
+    fn test_append_and_load() -> crate::error::Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "verandah-pomodoro-{}-history.jsonl",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+
+        let first = entry("work", Outcome::Completed);
+        let second = entry("short_break", Outcome::Skipped);
+        append(&path, std::slice::from_ref(&first))?;
+        append(&path, &[])?;
+        append(&path, std::slice::from_ref(&second))?;
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&path)?
+            .write_all(b"not json\n")?;
+
+        let loaded = load(&path);
+        fs::remove_file(&path)?;
+        assert_eq!(loaded?, vec![first, second]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_missing_file_is_empty() -> crate::error::Result<()> {
+        let path = std::env::temp_dir().join("verandah-pomodoro-no-such-history.jsonl");
+        assert!(load(path)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_entry_format() -> crate::error::Result<()> {
+        let line = serde_json::to_string(&entry("work", Outcome::Interrupted))?;
+        assert!(line.contains(r#""outcome":"interrupted""#));
+        assert!(line.contains(r#""kind":"work""#));
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+//! Shell commands run on timer events
+
+use std::collections::HashMap;
+use std::io;
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use strum::{AsRefStr, EnumString, VariantNames};
+
+use crate::schedule::PhaseKind;
+use crate::socket::Status;
+use crate::timer::{TimerEvent, format_secs};
+
+/// How often a running hook is checked for completion
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Timer events a hook command can be attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, AsRefStr, VariantNames)]
+#[strum(serialize_all = "snake_case")]
+pub enum HookEvent {
+    /// A work phase started running for the first time
+    OnWorkStart,
+    /// A break phase started running for the first time
+    OnBreakStart,
+    /// The timer was paused
+    OnPause,
+    /// The timer was started or resumed
+    OnResume,
+    /// The timer was reset
+    OnReset,
+    /// The current phase was skipped
+    OnSkip,
+}
+
+impl HookEvent {
+    /// The hook triggered by a timer event, if any
+    pub fn from_timer_event(event: TimerEvent) -> Option<Self> {
+        match event {
+            TimerEvent::Start {
+                kind: PhaseKind::Work,
+            } => Some(HookEvent::OnWorkStart),
+            TimerEvent::Start {
+                kind: PhaseKind::Break,
+            } => Some(HookEvent::OnBreakStart),
+            TimerEvent::Pause => Some(HookEvent::OnPause),
+            TimerEvent::Resume => Some(HookEvent::OnResume),
+            TimerEvent::Reset => Some(HookEvent::OnReset),
+            TimerEvent::Transition { skipped: true, .. } => Some(HookEvent::OnSkip),
+            TimerEvent::Transition { skipped: false, .. }
+            | TimerEvent::Tick
+            | TimerEvent::Overtime
+            | TimerEvent::Adjust => None,
+        }
+    }
+}
+
+/// Configured hook commands
+#[derive(Debug, Clone, Default)]
+pub struct Hooks {
+    commands: HashMap<HookEvent, String>,
+    /// How long a command may run before it is killed
+    timeout: Duration,
+}
+
+impl Hooks {
+    pub fn new(commands: HashMap<HookEvent, String>, timeout: Duration) -> Self {
+        Hooks { commands, timeout }
+    }
+
+    /// Run the command configured for `event` in a background thread, if any
+    pub fn run(&self, event: TimerEvent, status: &Status) {
+        let Some(hook) = HookEvent::from_timer_event(event) else {
+            return;
+        };
+        let Some(command) = self.commands.get(&hook) else {
+            return;
+        };
+
+        let command = command.clone();
+        let env = hook_env(hook, status);
+        let timeout = self.timeout;
+        thread::spawn(move || {
+            let hook = hook.as_ref();
+            match execute(&command, &env, timeout) {
+                Ok(Some(exit)) if exit.success() => {
+                    tracing::debug!(hook, command, "Hook finished");
+                }
+                Ok(Some(exit)) => {
+                    tracing::warn!(hook, command, %exit, "Hook failed");
+                }
+                Ok(None) => {
+                    tracing::warn!(hook, command, ?timeout, "Hook timed out and was killed");
+                }
+                Err(e) => {
+                    tracing::warn!(hook, command, error = %e, "Failed to run hook");
+                }
+            }
+        });
+    }
+}
+
+/// Environment variables describing the timer for a hook command
+fn hook_env(hook: HookEvent, status: &Status) -> Vec<(&'static str, String)> {
+    vec![
+        ("POMODORO_HOOK", hook.as_ref().to_string()),
+        ("POMODORO_PHASE", status.phase.clone()),
+        ("POMODORO_KIND", status.kind.clone()),
+        ("POMODORO_REMAINING", format_secs(status.remaining_secs)),
+        ("POMODORO_REMAINING_SECS", status.remaining_secs.to_string()),
+        ("POMODORO_DURATION_SECS", status.duration_secs.to_string()),
+        ("POMODORO_OVERTIME_SECS", status.overtime_secs.to_string()),
+        ("POMODORO_ELAPSED_SECS", status.elapsed_secs.to_string()),
+        ("POMODORO_RUNNING", status.running.to_string()),
+        ("POMODORO_ITERATIONS", status.iterations.to_string()),
+        ("POMODORO_SESSIONS", status.sessions_completed.to_string()),
+    ]
+}
+
+/// Run `command` through `sh -c`, killing it once `timeout` has passed
+/// Returns None if the command timed out
+fn execute(
+    command: &str,
+    env: &[(&'static str, String)],
+    timeout: Duration,
+) -> io::Result<Option<ExitStatus>> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .envs(env.iter().map(|(key, value)| (key, value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .spawn()?;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(exit) = child.try_wait()? {
+            return Ok(Some(exit));
+        }
+        if Instant::now() >= deadline {
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::Transition;
+
+    fn status() -> Status {
+        Status {
+            phase: "work".to_string(),
+            kind: "work".to_string(),
+            remaining_secs: 1500,
+            duration_secs: 1500,
+            running: true,
+            iterations: 1,
+            sessions_completed: 0,
+            overtime_secs: 0,
+            elapsed_secs: 0,
+        }
+    }
+
+    #[test]
+    fn test_hook_for_event() -> crate::error::Result<()> {
+        assert_eq!(
+            HookEvent::from_timer_event(TimerEvent::Start {
+                kind: PhaseKind::Break
+            }),
+            Some(HookEvent::OnBreakStart)
+        );
+        assert_eq!(
+            HookEvent::from_timer_event(TimerEvent::Transition {
+                transition: Transition::WorkComplete,
+                skipped: true,
+            }),
+            Some(HookEvent::OnSkip)
+        );
+        assert_eq!(
+            HookEvent::from_timer_event(TimerEvent::Transition {
+                transition: Transition::WorkComplete,
+                skipped: false,
+            }),
+            None
+        );
+        assert_eq!(HookEvent::from_timer_event(TimerEvent::Tick), None);
+        assert_eq!("on_work_start".parse(), Ok(HookEvent::OnWorkStart));
+        Ok(())
+    }
+
+    #[test]
+    fn test_execute_passes_environment() -> crate::error::Result<()> {
+        let env = hook_env(HookEvent::OnWorkStart, &status());
+        let check = r#"[ "$POMODORO_HOOK" = on_work_start ] && [ "$POMODORO_REMAINING" = 25:00 ] && [ "$POMODORO_RUNNING" = true ]"#;
+        let exit = execute(check, &env, Duration::from_secs(5))?;
+        assert!(exit.is_some_and(|exit| exit.success()));
+
+        let exit = execute("exit 3", &env, Duration::from_secs(5))?;
+        assert_eq!(exit.and_then(|exit| exit.code()), Some(3));
+        Ok(())
+    }
+
+    #[test]
+    fn test_execute_kills_after_timeout() -> crate::error::Result<()> {
+        let started = Instant::now();
+        let exit = execute("sleep 5", &[], Duration::from_millis(100))?;
+        assert_eq!(exit, None);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        Ok(())
+    }
+}
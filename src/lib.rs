@@ -7,26 +7,40 @@
 //! - Color-coded backgrounds
 
 use std::collections::HashMap;
-use std::sync::mpsc::Receiver;
-use std::time::Instant;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::SystemTime;
 
 use verandah_plugin::api::prelude::*;
 use verandah_plugin::utils::prelude::*;
 
-use std::path::PathBuf;
-
+mod animation;
+pub mod bar;
 pub mod cli;
 mod config;
 mod error;
+mod gradient;
+pub mod history;
+mod hooks;
+mod notify;
+pub mod protocol;
+mod registry;
 mod render;
+mod schedule;
 pub mod socket;
 mod sound;
+mod state;
+pub mod stats;
 mod timer;
 
+use animation::Animation;
 use config::{Config, ConfigBuilder, DEFAULT_INTERVAL_MS};
-use render::{FillDirection, PhaseIndicatorDisplay, RenderMode};
-use socket::{Command, SocketListener};
-use timer::{Phase, Timer, Transition};
+use gradient::Gradient;
+use registry::{SharedTimer, TimerHandle};
+use render::{
+    FillDirection, PhaseIndicatorDisplay, RadialStyle, RenderMode, Role, Slice, WarnStyle,
+};
+use socket::Command;
+use timer::Timer;
 
 const WIDGET_TYPE: &str = "pomodoro";
 
@@ -38,77 +52,181 @@ const DEFAULT_PAUSED_BG: Rgba<u8> = rgb("#7F8C8D");
 const DEFAULT_EMPTY_BG: Rgba<u8> = rgb("#2C3E50");
 const DEFAULT_DOT_RUNNING: Rgba<u8> = rgb("#008000");
 const DEFAULT_DOT_PAUSED: Rgba<u8> = rgb("#808080");
+const DEFAULT_GOAL: Rgba<u8> = rgb("#F1C40F");
+const DEFAULT_OVERTIME: Rgba<u8> = rgb("#F39C12");
+const DEFAULT_WARNING: Rgba<u8> = rgb("#E74C3C");
+
+// Poll interval while pulsing or blinking, and while a phase change animates
+const PULSE_INTERVAL_MS: u64 = 100;
+const TRANSITION_FRAME_MS: u64 = 40;
 
 struct PomodoroWidget {
-    timer: Timer,
+    // The timer this key shows, possibly shared with other keys
+    shared: TimerHandle,
     config: PluginConfig,
     interval: PluginDuration,
-    last_tick: Option<Instant>,
     // Parsed colors (keys: fg, work_bg, break_bg, paused_bg)
     colors: HashMap<String, Rgba<u8>>,
     padding: f32,
-    // Render mode and fill direction
+    // What this key shows of the timer
+    role: Role,
+    // Part of the row-wide bar drawn in the slice role
+    slice: Slice,
+    // Render mode, fill direction and radial shape
     render_mode: RenderMode,
     fill_direction: FillDirection,
+    radial: RadialStyle,
     phase_indicator_display: PhaseIndicatorDisplay,
     pulse_on_pause: bool,
-    phases: HashMap<String, String>,
-    // Labels/fallback text (keys: work, short_break, long_break, paused)
+    // How the key warns as a phase nears its end; the timer decides when
+    warn_style: WarnStyle,
+    // Labels/fallback text (keys: paused)
     labels: HashMap<String, String>,
-    // Sounds to play on phase transitions (keys: work, short_break, long_break)
-    // Sound indicates the STARTING phase, not the ending one
-    sounds: HashMap<String, PathBuf>,
-    // Socket control
-    command_rx: Option<Receiver<Command>>,
-    socket_listener: Option<SocketListener>,
+    // Per-phase color overrides (keyed by schedule index)
+    phase_colors: HashMap<usize, HashMap<String, Rgba<u8>>>,
+    // Background gradients (keys: phase names, work, break)
+    gradients: HashMap<String, Gradient>,
+    // Phase transition animation, advanced by both poll_state and render
+    animation: Mutex<Animation>,
 }
 
 impl PomodoroWidget {
     fn new() -> Self {
         let cfg = ConfigBuilder::default().build();
         PomodoroWidget {
-            timer: Timer::new(&cfg),
+            shared: Arc::new(Mutex::new(SharedTimer::detached(&cfg))),
             config: PluginConfig::new(),
             interval: PluginDuration::from_millis(DEFAULT_INTERVAL_MS),
-            last_tick: None,
             colors: parse_colors(&cfg.colors),
             padding: cfg.padding,
+            role: cfg.role,
+            slice: cfg.slice,
             render_mode: cfg.render_mode,
             fill_direction: cfg.fill_direction,
+            radial: cfg.radial,
             phase_indicator_display: cfg.phase_indicator_display,
             pulse_on_pause: cfg.pulse_on_pause,
-            phases: cfg.phases,
+            warn_style: cfg.warn_style,
             labels: cfg.labels,
-            sounds: HashMap::new(),
-            command_rx: None,
-            socket_listener: None,
+            phase_colors: HashMap::new(),
+            gradients: HashMap::new(),
+            animation: Mutex::new(Animation::new(cfg.transition, cfg.transition_length)),
         }
     }
 
-    fn start_socket_listener(&mut self) {
-        let (tx, rx) = socket::command_channel();
+    fn shared(&self) -> MutexGuard<'_, SharedTimer> {
+        registry::lock(&self.shared)
+    }
 
-        match SocketListener::new(tx) {
-            Ok(listener) => {
-                self.socket_listener = Some(listener);
-                self.command_rx = Some(rx);
-                tracing::info!("Socket control enabled");
-            }
-            Err(e) => {
-                tracing::warn!(error = %e, "Failed to start socket listener, control disabled");
-            }
-        }
+    fn animation(&self) -> MutexGuard<'_, Animation> {
+        self.animation
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Let go of the bound timer, leaving the key on a detached one from `cfg`
+    fn unbind(&mut self, cfg: &Config) {
+        let detached = Arc::new(Mutex::new(SharedTimer::detached(cfg)));
+        registry::release(std::mem::replace(&mut self.shared, detached));
+    }
+
+    /// Look up a color, preferring the override for the phase at `index`
+    fn color(&self, index: usize, key: &str, default: Rgba<u8>) -> Rgba<u8> {
+        self.phase_colors
+            .get(&index)
+            .and_then(|colors| colors.get(key))
+            .copied()
+            .unwrap_or_else(|| get_color(&self.colors, key, default))
     }
 
-    fn process_commands(&mut self) {
-        let Some(rx) = &self.command_rx else {
-            return;
+    /// Background color of the phase at `index`
+    fn phase_bg(&self, index: usize, is_break: bool) -> Rgba<u8> {
+        let phase_bg = if is_break {
+            self.color(index, "break_bg", DEFAULT_BREAK_BG)
+        } else {
+            self.color(index, "work_bg", DEFAULT_WORK_BG)
         };
+        self.color(index, "bg", phase_bg)
+    }
+
+    /// Background of the running phase, following its gradient if one is configured
+    fn progress_bg(&self, timer: &Timer) -> Rgba<u8> {
+        let phase = timer.phase();
+        let kind = if phase.is_break() { "break" } else { "work" };
+        self.gradients
+            .get(&phase.name)
+            .or_else(|| self.gradients.get(kind))
+            .map(|gradient| gradient.at(timer.progress_ratio()))
+            .unwrap_or_else(|| self.phase_bg(timer.phase_index(), phase.is_break()))
+    }
 
-        while let Ok(cmd) = rx.try_recv() {
-            tracing::debug!(command = ?cmd, "Processing command");
-            cmd.apply(&mut self.timer);
+    /// True while a countdown key's timer is in its final `warn_before` stretch
+    fn is_warning(&self, shared: &SharedTimer) -> bool {
+        self.role == Role::Countdown && shared.is_warning()
+    }
+
+    fn label<'a>(&'a self, key: &str, default: &'a str) -> &'a str {
+        self.labels.get(key).map(String::as_str).unwrap_or(default)
+    }
+
+    /// Render the `countdown` role: the timer in the configured render mode
+    fn render_countdown(
+        &self,
+        shared: &SharedTimer,
+        images: &RHashMap<RString, PluginImage>,
+        image_size: PluginImageSize,
+    ) -> RgbImage {
+        let timer = &shared.timer;
+        let index = timer.phase_index();
+        let phase = timer.phase();
+
+        // Get phase icon (used for fill_icon mode and paused display)
+        let phase_icon = images.get(&RString::from(phase.icon.as_str()));
+
+        // Get icon and fallback text for phase boundary display
+        let (paused_icon, fallback_text): (Option<&PluginImage>, Option<&str>) =
+            if !timer.is_running() && timer.at_phase_boundary() {
+                (phase_icon, Some(phase.label.as_str()))
+            } else {
+                (None, None)
+            };
+
+        // Overtime is counted up in its own color
+        let fg_color = if timer.is_overtime() {
+            self.color(index, "overtime", DEFAULT_OVERTIME)
+        } else {
+            self.color(index, "fg", DEFAULT_FG)
+        };
+
+        let mut rgb = render::render_button(
+            timer,
+            image_size.width,
+            image_size.height,
+            fg_color,
+            self.progress_bg(timer),
+            self.color(index, "paused_bg", DEFAULT_PAUSED_BG),
+            self.color(index, "empty_bg", DEFAULT_EMPTY_BG),
+            self.color(index, "dot_running", DEFAULT_DOT_RUNNING),
+            self.color(index, "dot_paused", DEFAULT_DOT_PAUSED),
+            self.color(index, "goal", DEFAULT_GOAL),
+            self.padding,
+            paused_icon,
+            phase_icon,
+            fallback_text,
+            self.label("paused", "PAUSED"),
+            self.render_mode,
+            self.fill_direction,
+            self.radial,
+            self.phase_indicator_display,
+            self.pulse_on_pause,
+        );
+
+        if self.is_warning(shared) {
+            let on = render::warning_blink_on(SystemTime::now());
+            let warning_color = self.color(index, "warning", DEFAULT_WARNING);
+            render::apply_warning(&mut rgb, self.warn_style, warning_color, on);
         }
+        rgb
     }
 }
 
@@ -129,33 +247,55 @@ impl WidgetPlugin for PomodoroWidget {
             PluginResult::RErr(e) => return PluginResult::RErr(e),
         };
 
-        self.timer = Timer::new(&cfg);
+        // The old timer must close before its replacement opens the same socket
+        self.unbind(&cfg);
+        self.shared = registry::bind(&cfg);
         self.interval = PluginDuration::from_millis(cfg.interval);
         self.colors = parse_colors(&cfg.colors);
         self.padding = cfg.padding.clamp(0.0, 0.4);
+        self.role = cfg.role;
+        self.slice = cfg.slice;
         self.render_mode = cfg.render_mode;
         self.fill_direction = cfg.fill_direction;
+        self.radial = cfg.radial;
         self.phase_indicator_display = cfg.phase_indicator_display;
         self.pulse_on_pause = cfg.pulse_on_pause;
-        self.phases = cfg.phases;
+        self.warn_style = cfg.warn_style;
         self.labels = cfg.labels;
+        self.animation = Mutex::new(Animation::new(cfg.transition, cfg.transition_length));
 
-        // Resolve sound paths
-        self.sounds.clear();
-        for (key, sound_name) in &cfg.sounds {
-            if let Some(path) = sound::resolve_sound(sound_name) {
-                tracing::info!(key, path = %path.display(), "Sound configured");
-                self.sounds.insert(key.clone(), path);
+        // Resolve per-phase color overrides
+        self.phase_colors.clear();
+        for (index, phase) in cfg.schedule.phases().iter().enumerate() {
+            if !phase.colors.is_empty() {
+                self.phase_colors.insert(index, parse_colors(&phase.colors));
             }
         }
 
-        // Start the socket listener for external control
-        self.start_socket_listener();
+        self.gradients = cfg
+            .gradients
+            .iter()
+            .filter_map(|(key, stops)| {
+                let gradient = Gradient::parse(stops);
+                if gradient.is_none() {
+                    tracing::warn!(gradient = key, "Gradient has no valid colors, ignoring");
+                }
+                Some((key.clone(), gradient?))
+            })
+            .collect();
 
         tracing::info!(
             work_mins = cfg.work,
             short_break_mins = cfg.short_break,
             long_break_mins = cfg.long_break,
+            timer = cfg.timer.as_deref(),
+            role = cfg.role.as_ref(),
+            schedule = ?cfg
+                .schedule
+                .phases()
+                .iter()
+                .map(|p| p.name.as_str())
+                .collect::<Vec<_>>(),
             "Pomodoro widget initialized"
         );
 
@@ -167,63 +307,64 @@ impl WidgetPlugin for PomodoroWidget {
     }
 
     fn poll_state(&mut self) -> PluginResult<PluginPollResponse> {
-        // Process any pending commands from socket
-        self.process_commands();
-
-        let now = Instant::now();
-
-        // Tick the timer for each elapsed second to prevent drift under load
-        if let Some(last) = self.last_tick {
-            let elapsed_secs = now.duration_since(last).as_secs();
-            if elapsed_secs >= 1 {
-                self.last_tick = Some(now);
-
-                for _ in 0..elapsed_secs {
-                    let transition = self.timer.tick();
-
-                    // Play sound on phase transition
-                    // Sound indicates the phase that is STARTING, not the one that ended
-                    if transition != Transition::None {
-                        let sound_key = match self.timer.phase() {
-                            Phase::Work => "work",
-                            Phase::ShortBreak => "short_break",
-                            Phase::LongBreak => "long_break",
-                        };
-                        if let Some(path) = self.sounds.get(sound_key) {
-                            sound::play_sound(path);
-                        }
-                    }
-                }
-            }
-        } else {
-            self.last_tick = Some(now);
-        }
+        // Process pending commands and bring the timer up to date with the clock
+        let mut shared = self.shared();
+        shared.poll();
+        let timer = &shared.timer;
 
         // Return the formatted time and running state
-        // Include running state so UI updates when paused/resumed
+        // Include running state so UI updates when paused/resumed,
+        // goal progress so the key redraws when the celebration ends,
+        // the blink state so a warning key redraws as it blinks,
+        // and the transition progress so every animation frame is drawn
+        let goal = timer
+            .goal_progress()
+            .map(|g| format!("|{}{}", g.done, if g.celebrating { "!" } else { "" }))
+            .unwrap_or_default();
+        let warning = self.is_warning(&shared);
+        let blink = if warning {
+            let on = render::warning_blink_on(SystemTime::now());
+            if on { "|W" } else { "|w" }
+        } else {
+            ""
+        };
+        let transition = self.animation().observe(shared.transitions());
+        let frame = transition
+            .map(|t| format!("|T{}", (t * 100.0) as u32))
+            .unwrap_or_default();
         let text = format!(
-            "{}|{}",
-            self.timer.remaining_formatted(),
-            if self.timer.is_running() { "R" } else { "P" }
+            "{}|{}{goal}{blink}{frame}",
+            timer.remaining_formatted(),
+            if timer.is_running() { "R" } else { "P" }
         );
         let state = PluginWidgetState::Text(text.into());
 
-        // Use fast interval for smooth pulse animation when paused
-        let should_pulse =
-            self.pulse_on_pause && !self.timer.is_running() && !self.timer.at_phase_boundary();
+        // Use fast interval for smooth pulse animation when paused, and to
+        // keep the warning blink in step; faster still while a phase change
+        // animates
+        let should_pulse = self.role == Role::Countdown
+            && self.pulse_on_pause
+            && !timer.is_running()
+            && !timer.at_phase_boundary();
 
         tracing::trace!(
             pulse_on_pause = self.pulse_on_pause,
-            is_running = self.timer.is_running(),
-            at_phase_boundary = self.timer.at_phase_boundary(),
+            is_running = timer.is_running(),
+            at_phase_boundary = timer.at_phase_boundary(),
             should_pulse,
+            warning,
             "poll_state"
         );
 
-        if should_pulse {
+        if transition.is_some() {
             PluginResult::ROk(PluginPollResponse::with_interval(
                 state,
-                PluginDuration::from_millis(100),
+                PluginDuration::from_millis(TRANSITION_FRAME_MS),
+            ))
+        } else if should_pulse || warning {
+            PluginResult::ROk(PluginPollResponse::with_interval(
+                state,
+                PluginDuration::from_millis(PULSE_INTERVAL_MS),
             ))
         } else {
             PluginResult::ROk(PluginPollResponse::state(state))
@@ -236,58 +377,56 @@ impl WidgetPlugin for PomodoroWidget {
         _state: &PluginWidgetState,
         image_size: PluginImageSize,
     ) -> PluginResult<PluginImage> {
-        let phase = self.timer.phase();
-        let icon_key = match phase {
-            Phase::Work => "work",
-            Phase::ShortBreak => "short_break",
-            Phase::LongBreak => "long_break",
+        let shared = self.shared();
+        let timer = &shared.timer;
+        let index = timer.phase_index();
+
+        let rgb_img = match self.role {
+            Role::Countdown => self.render_countdown(&shared, &images, image_size),
+            Role::Today => render::render_today(
+                timer,
+                image_size.width,
+                image_size.height,
+                self.color(index, "fg", DEFAULT_FG),
+                self.color(index, "empty_bg", DEFAULT_EMPTY_BG),
+                self.color(index, "goal", DEFAULT_GOAL),
+                self.padding,
+                self.label("today", "today"),
+            ),
+            Role::Skip => {
+                let next = timer.next_phase_index();
+                render::render_skip(
+                    timer,
+                    image_size.width,
+                    image_size.height,
+                    self.color(next, "fg", DEFAULT_FG),
+                    self.phase_bg(next, timer.next_phase().is_break()),
+                    self.color(index, "paused_bg", DEFAULT_PAUSED_BG),
+                    self.padding,
+                    self.label("skip", "skip"),
+                )
+            }
+            Role::Slice => render::render_slice(
+                timer,
+                image_size.width,
+                image_size.height,
+                self.slice,
+                self.color(index, "fg", DEFAULT_FG),
+                self.phase_bg(index, timer.phase().is_break()),
+                self.color(index, "paused_bg", DEFAULT_PAUSED_BG),
+                self.color(index, "empty_bg", DEFAULT_EMPTY_BG),
+                if timer.is_running() {
+                    self.color(index, "dot_running", DEFAULT_DOT_RUNNING)
+                } else {
+                    self.color(index, "dot_paused", DEFAULT_DOT_PAUSED)
+                },
+                self.fill_direction,
+            ),
         };
 
-        // Get phase icon (used for fill_icon mode and paused display)
-        let phase_icon = images.get(&RString::from(icon_key));
-
-        // Get icon and fallback text for phase boundary display
-        let (paused_icon, fallback_text): (Option<&PluginImage>, Option<&str>) =
-            if !self.timer.is_running() && self.timer.at_phase_boundary() {
-                let fallback = self
-                    .labels
-                    .get(icon_key)
-                    .map(|s: &String| s.as_str())
-                    .unwrap_or(match phase {
-                        Phase::Work => "Work",
-                        Phase::ShortBreak => "Short\nBreak",
-                        Phase::LongBreak => "Long\nBreak",
-                    });
-                (phase_icon, Some(fallback))
-            } else {
-                (None, None)
-            };
-
-        let rgb_img = render::render_button(
-            &self.timer,
-            image_size.width,
-            image_size.height,
-            get_color(&self.colors, "fg", DEFAULT_FG),
-            get_color(&self.colors, "work_bg", DEFAULT_WORK_BG),
-            get_color(&self.colors, "break_bg", DEFAULT_BREAK_BG),
-            get_color(&self.colors, "paused_bg", DEFAULT_PAUSED_BG),
-            get_color(&self.colors, "empty_bg", DEFAULT_EMPTY_BG),
-            get_color(&self.colors, "dot_running", DEFAULT_DOT_RUNNING),
-            get_color(&self.colors, "dot_paused", DEFAULT_DOT_PAUSED),
-            self.padding,
-            paused_icon,
-            phase_icon,
-            fallback_text,
-            self.labels
-                .get("paused")
-                .map(|s: &String| s.as_str())
-                .unwrap_or("PAUSED"),
-            &self.phases,
-            self.render_mode,
-            self.fill_direction,
-            self.phase_indicator_display,
-            self.pulse_on_pause,
-        );
+        // Blend in from the previous phase while a phase change animates
+        let flash_color = self.color(index, "fg", DEFAULT_FG);
+        let rgb_img = self.animation().frame(rgb_img, flash_color);
 
         PluginResult::ROk(PluginImage::from_rgb(
             rgb_img.width(),
@@ -298,31 +437,59 @@ impl WidgetPlugin for PomodoroWidget {
 
     fn shutdown(&mut self) {
         tracing::info!("Pomodoro widget shutting down");
-        if let Some(mut listener) = self.socket_listener.take() {
-            listener.shutdown();
-        }
+        // The last widget bound to a timer takes its socket down with it
+        self.unbind(&ConfigBuilder::default().build());
     }
 
     fn supported_actions(&self) -> RVec<PluginActionSpec> {
+        // A bare press on a skip key skips
+        let default_verb = match self.role {
+            Role::Skip => "skip",
+            Role::Countdown | Role::Today | Role::Slice => "toggle",
+        };
         vec![
-            PluginActionSpec::new("toggle", "Toggle the timer between running and paused")
-                .with_default(),
+            PluginActionSpec::new("toggle", "Toggle the timer between running and paused"),
             PluginActionSpec::new("start", "Start the timer"),
             PluginActionSpec::new("stop", "Stop/pause the timer"),
             PluginActionSpec::new("reset", "Reset the timer to the beginning"),
             PluginActionSpec::new("skip", "Skip to the next phase"),
+            PluginActionSpec::new(
+                "extend:<duration>",
+                "Lengthen the current phase, e.g. extend:5m",
+            ),
+            PluginActionSpec::new(
+                "shorten:<duration>",
+                "Shorten the current phase, e.g. shorten:2m",
+            ),
+            PluginActionSpec::new(
+                "set:<duration>",
+                "Set the time remaining in the current phase, e.g. set:10m",
+            ),
+            PluginActionSpec::new(
+                "phase:<name>",
+                "Jump to the start of a phase, e.g. phase:long_break",
+            ),
         ]
-        .into()
+        .into_iter()
+        .map(|spec| {
+            if spec.name.as_str() == default_verb {
+                spec.with_default()
+            } else {
+                spec
+            }
+        })
+        .collect()
     }
 
     fn handle_action(&mut self, verb: RStr<'_>) -> PluginResult<()> {
-        match Command::parse(verb.as_str()) {
-            Some(cmd) => {
-                tracing::info!(verb = verb.as_str(), "Applying plugin action");
-                cmd.apply(&mut self.timer);
-                PluginResult::ROk(())
-            }
-            None => PluginResult::RErr(PluginError::new(format!("Unsupported action: {verb}"))),
+        let result = Command::parse(verb.as_str()).and_then(|cmd| {
+            tracing::info!(verb = verb.as_str(), "Applying plugin action");
+            self.shared().apply(&cmd)
+        });
+
+        match result {
+            Ok(_) => PluginResult::ROk(()),
+            Err(e) => PluginResult::RErr(PluginError::new(format!("Action {verb} failed: {e}"))),
         }
     }
 }
@@ -347,11 +514,24 @@ mod tests {
     use super::*;
 
     #[test]
-    fn supported_actions_declares_five_verbs_with_toggle_default() -> error::Result<()> {
+    fn supported_actions_declares_verbs_with_toggle_default() -> error::Result<()> {
         let widget = PomodoroWidget::new();
         let actions = widget.supported_actions();
         let names: Vec<&str> = actions.iter().map(|a| a.name.as_str()).collect();
-        assert_eq!(names, vec!["toggle", "start", "stop", "reset", "skip"]);
+        assert_eq!(
+            names,
+            vec![
+                "toggle",
+                "start",
+                "stop",
+                "reset",
+                "skip",
+                "extend:<duration>",
+                "shorten:<duration>",
+                "set:<duration>",
+                "phase:<name>",
+            ]
+        );
         let defaults: Vec<&str> = actions
             .iter()
             .filter(|a| a.is_default)
@@ -361,14 +541,65 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn supported_actions_default_follows_role() -> error::Result<()> {
+        let mut widget = PomodoroWidget::new();
+        widget.role = Role::Skip;
+        let actions = widget.supported_actions();
+        let defaults: Vec<&str> = actions
+            .iter()
+            .filter(|a| a.is_default)
+            .map(|a| a.name.as_str())
+            .collect();
+        assert_eq!(defaults, vec!["skip"]);
+        Ok(())
+    }
+
+    #[test]
+    fn progress_bg_prefers_the_phase_gradient() -> error::Result<()> {
+        let mut widget = PomodoroWidget::new();
+        let mut timer = Timer::new(&ConfigBuilder::default().build());
+        assert_eq!(widget.progress_bg(&timer), DEFAULT_WORK_BG);
+
+        let red = rgb("#FF0000");
+        let blue = rgb("#0000FF");
+        let (Some(red_to_blue), Some(all_red), Some(all_blue)) = (
+            Gradient::new(vec![red, blue]),
+            Gradient::new(vec![red]),
+            Gradient::new(vec![blue]),
+        ) else {
+            panic!("gradients with stops should build");
+        };
+        widget.gradients.insert("work".to_string(), red_to_blue);
+        assert_eq!(widget.progress_bg(&timer), red);
+
+        widget.gradients.insert("break".to_string(), all_red);
+        widget.gradients.insert("short_break".to_string(), all_blue);
+        assert!(timer.jump_to("short_break"));
+        assert_eq!(widget.progress_bg(&timer), blue);
+        Ok(())
+    }
+
     #[test]
     fn handle_action_toggle_flips_timer_running() -> error::Result<()> {
         let mut widget = PomodoroWidget::new();
-        assert!(!widget.timer.is_running());
+        assert!(!widget.shared().timer.is_running());
         assert!(widget.handle_action("toggle".into()).is_ok());
-        assert!(widget.timer.is_running());
+        assert!(widget.shared().timer.is_running());
         assert!(widget.handle_action("toggle".into()).is_ok());
-        assert!(!widget.timer.is_running());
+        assert!(!widget.shared().timer.is_running());
+        Ok(())
+    }
+
+    #[test]
+    fn handle_action_accepts_parameterised_verbs() -> error::Result<()> {
+        let mut widget = PomodoroWidget::new();
+        assert!(widget.handle_action("extend:5m".into()).is_ok());
+        assert_eq!(widget.shared().timer.remaining_secs(), 30 * 60);
+        assert!(widget.handle_action("phase:long_break".into()).is_ok());
+        assert_eq!(widget.shared().timer.phase().name, "long_break");
+        assert!(widget.handle_action("phase:lunch".into()).is_err());
+        assert!(widget.handle_action("extend:later".into()).is_err());
         Ok(())
     }
 
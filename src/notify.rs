@@ -0,0 +1,333 @@
+//! Desktop notifications through the freedesktop notification service
+//!
+//! A notification is posted whenever a new phase comes up. Its action
+//! buttons are parsed as control verbs and fed into the same command
+//! channel as the socket, so they act exactly like `verandah-pomodoroctl`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+use zbus::blocking::Connection;
+use zbus::zvariant::Value;
+
+use crate::schedule::PhaseSpec;
+use crate::socket::{Command, Request, Status};
+use crate::timer::{Timer, format_secs};
+
+/// Application name shown by the notification server
+const APP_NAME: &str = "Pomodoro";
+
+/// Let the server pick how long notifications stay up
+const DEFAULT_EXPIRE_TIMEOUT: i32 = -1;
+
+#[zbus::proxy(
+    interface = "org.freedesktop.Notifications",
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/org/freedesktop/Notifications"
+)]
+trait Notifications {
+    #[allow(clippy::too_many_arguments)]
+    fn notify(
+        &self,
+        app_name: &str,
+        replaces_id: u32,
+        app_icon: &str,
+        summary: &str,
+        body: &str,
+        actions: &[&str],
+        hints: HashMap<&str, &Value<'_>>,
+        expire_timeout: i32,
+    ) -> zbus::Result<u32>;
+
+    #[zbus(signal)]
+    fn action_invoked(&self, id: u32, action_key: String) -> zbus::Result<()>;
+}
+
+/// A notification ready to be posted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub summary: String,
+    pub body: String,
+    /// `(verb, button label)` pairs
+    pub actions: Vec<(String, String)>,
+}
+
+impl Notification {
+    /// The notification for the timer's current phase
+    pub fn for_phase(timer: &Timer) -> Self {
+        let phase = timer.phase();
+        let status = Status::from(timer);
+        let label = phase.label.replace('\n', " ");
+
+        let mut actions = Vec::new();
+        if !status.running {
+            actions.push((
+                "start".to_string(),
+                format!("Start {}", label.to_lowercase()),
+            ));
+        }
+        actions.push(("skip".to_string(), "Skip".to_string()));
+        actions.push(("extend:5m".to_string(), "+5 min".to_string()));
+
+        Notification {
+            summary: render_template(&phase.notify_summary, phase, &label, &status),
+            body: render_template(&phase.notify_body, phase, &label, &status),
+            actions,
+        }
+    }
+}
+
+/// Substitute the phase placeholders in `template`
+fn render_template(template: &str, phase: &PhaseSpec, label: &str, status: &Status) -> String {
+    template
+        .replace("{phase}", &phase.name)
+        .replace("{label}", label)
+        .replace("{kind}", &status.kind)
+        .replace("{duration}", &format_secs(status.duration_secs))
+        .replace("{remaining}", &format_secs(status.remaining_secs))
+        .replace("{iterations}", &status.iterations.to_string())
+        .replace("{sessions}", &status.sessions_completed.to_string())
+}
+
+/// Posts notifications and routes their actions back to the timer
+pub struct Notifier {
+    proxy: NotificationsProxyBlocking<'static>,
+    /// Id of the last notification posted, replaced by the next one
+    last_id: Arc<AtomicU32>,
+}
+
+impl Notifier {
+    /// Connect to the notification service on the session bus
+    pub fn connect(commands: Sender<Request>) -> zbus::Result<Self> {
+        Self::with_connection(&Connection::session()?, commands)
+    }
+
+    /// Use the notification service on `connection`, sending invoked actions to `commands`
+    pub fn with_connection(
+        connection: &Connection,
+        commands: Sender<Request>,
+    ) -> zbus::Result<Self> {
+        let proxy = NotificationsProxyBlocking::new(connection)?;
+        let last_id = Arc::new(AtomicU32::new(0));
+
+        // Subscribe before returning so no action on a posted notification can be missed
+        let signals = proxy.receive_action_invoked()?;
+        let ours = Arc::clone(&last_id);
+        thread::spawn(move || {
+            for signal in signals {
+                let Ok(args) = signal.args() else {
+                    continue;
+                };
+                // Every application's notification actions are broadcast on the bus
+                let id = ours.load(Ordering::Relaxed);
+                if id == 0 || args.id != id {
+                    continue;
+                }
+                let command = match Command::parse(&args.action_key) {
+                    Ok(command) => command,
+                    Err(e) => {
+                        tracing::debug!(
+                            action = args.action_key,
+                            error = %e,
+                            "Ignoring notification action"
+                        );
+                        continue;
+                    }
+                };
+                // Nobody waits for the reply to a notification action
+                let (reply, _) = mpsc::channel();
+                if commands.send(Request { command, reply }).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Notifier { proxy, last_id })
+    }
+
+    /// Post `notification` in a background thread, replacing the previous one
+    pub fn notify(&self, notification: Notification) {
+        let notifier = Notifier {
+            proxy: self.proxy.clone(),
+            last_id: Arc::clone(&self.last_id),
+        };
+        thread::spawn(move || {
+            if let Err(e) = notifier.send(&notification) {
+                tracing::warn!(error = %e, "Failed to post notification");
+            }
+        });
+    }
+
+    /// Post `notification` and wait for the server to accept it
+    fn send(&self, notification: &Notification) -> zbus::Result<u32> {
+        let actions: Vec<&str> = notification
+            .actions
+            .iter()
+            .flat_map(|(verb, label)| [verb.as_str(), label.as_str()])
+            .collect();
+
+        let id = self.proxy.notify(
+            APP_NAME,
+            self.last_id.load(Ordering::Relaxed),
+            "",
+            &notification.summary,
+            &notification.body,
+            &actions,
+            HashMap::new(),
+            DEFAULT_EXPIRE_TIMEOUT,
+        )?;
+        self.last_id.store(id, Ordering::Relaxed);
+        Ok(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixStream;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use zbus::zvariant::OwnedValue;
+
+    const PATH: &str = "/org/freedesktop/Notifications";
+    const INTERFACE: &str = "org.freedesktop.Notifications";
+
+    /// Stand-in notification server recording what it is sent
+    #[derive(Default)]
+    struct StandIn {
+        posted: Arc<Mutex<Vec<(u32, String, String, Vec<String>)>>>,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.Notifications")]
+    impl StandIn {
+        #[allow(clippy::too_many_arguments)]
+        fn notify(
+            &self,
+            _app_name: String,
+            replaces_id: u32,
+            _app_icon: String,
+            summary: String,
+            body: String,
+            actions: Vec<String>,
+            _hints: HashMap<String, OwnedValue>,
+            _expire_timeout: i32,
+        ) -> u32 {
+            if let Ok(mut posted) = self.posted.lock() {
+                posted.push((replaces_id, summary, body, actions));
+            }
+            42
+        }
+    }
+
+    /// Connect a client to a stand-in server over a private peer-to-peer bus
+    fn private_bus(server: StandIn) -> crate::error::Result<(Connection, Connection)> {
+        let (server_end, client_end) = UnixStream::pair()?;
+        let guid = zbus::Guid::generate();
+        let server = thread::spawn(move || {
+            zbus::blocking::connection::Builder::unix_stream(server_end)
+                .server(guid)?
+                .p2p()
+                .serve_at(PATH, server)?
+                .build()
+        });
+        let client = zbus::blocking::connection::Builder::unix_stream(client_end)
+            .p2p()
+            .build();
+
+        let Ok(server) = server.join() else {
+            panic!("stand-in server thread panicked");
+        };
+        let (Ok(server), Ok(client)) = (server, client) else {
+            panic!("private bus handshake failed");
+        };
+        Ok((server, client))
+    }
+
+    fn test_timer() -> crate::error::Result<Timer> {
+        use crate::config::ConfigBuilder;
+        let toml_str = r#"
+[notify_summary]
+short_break = "{label} after {iterations} pomodoro"
+"#;
+        let config = verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        Ok(Timer::new(&config))
+    }
+
+    #[test]
+    fn test_notification_for_phase() -> crate::error::Result<()> {
+        let mut timer = test_timer()?;
+        let notification = Notification::for_phase(&timer);
+        assert_eq!(notification.summary, "Work");
+        assert_eq!(notification.body, "25:00 of focus");
+        assert_eq!(
+            notification.actions[0],
+            ("start".to_string(), "Start work".to_string())
+        );
+
+        timer.start();
+        timer.skip();
+        let notification = Notification::for_phase(&timer);
+        assert_eq!(notification.summary, "Short Break after 1 pomodoro");
+        assert_eq!(notification.body, "05:00 break");
+        let verbs: Vec<_> = notification
+            .actions
+            .iter()
+            .map(|(verb, _)| verb.as_str())
+            .collect();
+        assert_eq!(verbs, vec!["start", "skip", "extend:5m"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_notifier_against_stand_in_server() -> crate::error::Result<()> {
+        let stand_in = StandIn::default();
+        let posted = Arc::clone(&stand_in.posted);
+        let (server, client) = private_bus(stand_in)?;
+        let (tx, rx) = crate::socket::command_channel();
+        let Ok(notifier) = Notifier::with_connection(&client, tx) else {
+            panic!("notifier should connect to the stand-in server");
+        };
+
+        let notification = Notification::for_phase(&test_timer()?);
+        let Ok(id) = notifier.send(&notification) else {
+            panic!("stand-in server should accept the notification");
+        };
+        assert_eq!(id, 42);
+        // The next notification replaces the previous one
+        assert!(notifier.send(&notification).is_ok());
+        let Ok(posted) = posted.lock() else {
+            panic!("stand-in server lock poisoned");
+        };
+        assert_eq!(posted.len(), 2);
+        assert_eq!((posted[0].0, posted[1].0), (0, 42));
+        assert_eq!(posted[0].1, "Work");
+        assert_eq!(
+            posted[0].3,
+            vec!["start", "Start work", "skip", "Skip", "extend:5m", "+5 min"]
+        );
+        drop(posted);
+
+        // Actions on another application's notification are not ours
+        let emit = |id: u32, action: &str| {
+            server.emit_signal(
+                None::<&str>,
+                PATH,
+                INTERFACE,
+                "ActionInvoked",
+                &(id, action),
+            )
+        };
+        assert!(emit(7, "skip").is_ok());
+        assert!(emit(42, "extend:5m").is_ok());
+        let request = rx.recv_timeout(Duration::from_secs(5));
+        assert_eq!(
+            request.map(|r| r.command).ok(),
+            Some(Command::Extend(Duration::from_secs(300)))
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,317 @@
+//! Newline-delimited JSON control protocol
+//!
+//! Each request is a single JSON object on its own line; the server answers
+//! every request with exactly one JSON object line. A connection is switched
+//! to this protocol when its first line starts with `{`, otherwise the plain
+//! word form (`toggle`, `status`, ...) is used.
+//!
+//! After a successful `subscribe` the connection only carries event lines,
+//! one per timer event, until either side closes it.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::socket::{Command, Reply, Status};
+pub use crate::timer::{TimerEvent, Transition};
+
+/// Current protocol version
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Name reported in the handshake
+const SERVER_NAME: &str = "verandah-plugin-pomodoro";
+
+/// `result` of a successful subscribe response
+const SUBSCRIBED: &str = "subscribed";
+
+/// Machine-readable error codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The request line was not a valid request object
+    InvalidRequest,
+    /// The request asked for a protocol version this server does not speak
+    UnsupportedVersion,
+    /// The `cmd` is not a known command
+    UnknownCommand,
+    /// The command's argument is missing or invalid
+    InvalidArgument,
+    /// The plugin did not answer in time
+    Timeout,
+    /// The plugin is shutting down
+    Unavailable,
+}
+
+/// A request sent by a client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClientRequest {
+    /// Protocol version the request is written against
+    #[serde(default = "default_version")]
+    pub v: u32,
+    /// Opaque value echoed back in the response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    /// `hello` for the handshake, `subscribe` for the event stream, or any command verb
+    pub cmd: String,
+}
+
+fn default_version() -> u32 {
+    PROTOCOL_VERSION
+}
+
+/// Error details in a failed response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorBody {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+/// A response sent by the server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerResponse {
+    pub v: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    pub ok: bool,
+    /// `ok` or `unchanged` for commands, `hello` for the handshake, `subscribed` for subscribe
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    /// Server name, only in the handshake response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorBody>,
+}
+
+impl ServerResponse {
+    fn new(id: Option<Value>, ok: bool) -> Self {
+        ServerResponse {
+            v: PROTOCOL_VERSION,
+            id,
+            ok,
+            result: None,
+            status: None,
+            server: None,
+            error: None,
+        }
+    }
+
+    fn hello(id: Option<Value>) -> Self {
+        ServerResponse {
+            result: Some("hello".to_string()),
+            server: Some(SERVER_NAME.to_string()),
+            ..Self::new(id, true)
+        }
+    }
+
+    fn error<S>(id: Option<Value>, code: ErrorCode, message: S) -> Self
+    where
+        S: Into<String>,
+    {
+        ServerResponse {
+            error: Some(ErrorBody {
+                code,
+                message: message.into(),
+            }),
+            ..Self::new(id, false)
+        }
+    }
+
+    fn from_reply(id: Option<Value>, reply: Reply) -> Self {
+        match reply {
+            Reply::Ok(status) => ServerResponse {
+                result: Some("ok".to_string()),
+                status: Some(status),
+                ..Self::new(id, true)
+            },
+            Reply::Unchanged(status) => ServerResponse {
+                result: Some("unchanged".to_string()),
+                status: Some(status),
+                ..Self::new(id, true)
+            },
+            Reply::Error(code, message) => Self::error(id, code, message),
+        }
+    }
+
+    /// True if this response starts an event stream on the connection
+    pub fn is_subscription(&self) -> bool {
+        self.ok && self.result.as_deref() == Some(SUBSCRIBED)
+    }
+
+    /// Serialize as a single protocol line, including the trailing newline
+    pub fn to_line(&self) -> String {
+        to_line(self)
+    }
+}
+
+/// A timer event pushed to subscribers, with the timer state after it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventLine {
+    pub v: u32,
+    #[serde(flatten)]
+    pub event: TimerEvent,
+    pub status: Status,
+}
+
+impl EventLine {
+    pub fn new(event: TimerEvent, status: Status) -> Self {
+        EventLine {
+            v: PROTOCOL_VERSION,
+            event,
+            status,
+        }
+    }
+
+    /// Serialize as a single protocol line, including the trailing newline
+    pub fn to_line(&self) -> String {
+        to_line(self)
+    }
+}
+
+fn to_line<T>(value: &T) -> String
+where
+    T: Serialize,
+{
+    // Serializing plain data structs cannot fail
+    let mut line = serde_json::to_string(value).unwrap_or_default();
+    line.push('\n');
+    line
+}
+
+/// Handle one request line, forwarding commands to `dispatch`
+pub fn handle_line<F>(line: &str, dispatch: F) -> ServerResponse
+where
+    F: FnOnce(Command) -> Reply,
+{
+    let request: ClientRequest = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => return ServerResponse::error(None, ErrorCode::InvalidRequest, e.to_string()),
+    };
+
+    if request.v > PROTOCOL_VERSION {
+        return ServerResponse::error(
+            request.id,
+            ErrorCode::UnsupportedVersion,
+            format!(
+                "protocol version {} is not supported, server speaks {PROTOCOL_VERSION}",
+                request.v
+            ),
+        );
+    }
+
+    match request.cmd.as_str() {
+        "hello" => return ServerResponse::hello(request.id),
+        // Start the stream with the current state so clients need not query it separately
+        "subscribe" => {
+            let mut response = ServerResponse::from_reply(request.id, dispatch(Command::Status));
+            if response.ok {
+                response.result = Some(SUBSCRIBED.to_string());
+            }
+            return response;
+        }
+        _ => {}
+    }
+
+    match Command::parse(&request.cmd) {
+        Ok(command) => ServerResponse::from_reply(request.id, dispatch(command)),
+        Err(e) => ServerResponse::from_reply(request.id, Reply::from(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status() -> Status {
+        Status {
+            phase: "work".to_string(),
+            kind: "work".to_string(),
+            remaining_secs: 1500,
+            duration_secs: 1500,
+            running: false,
+            iterations: 0,
+            sessions_completed: 0,
+            overtime_secs: 0,
+            elapsed_secs: 0,
+        }
+    }
+
+    #[test]
+    fn test_handshake() -> crate::error::Result<()> {
+        let response = handle_line(r#"{"cmd":"hello","v":1,"id":7}"#, |_| {
+            panic!("handshake must not reach the timer")
+        });
+        assert!(response.ok);
+        assert_eq!(response.v, PROTOCOL_VERSION);
+        assert_eq!(response.id, Some(Value::from(7)));
+        assert_eq!(response.server.as_deref(), Some(SERVER_NAME));
+        Ok(())
+    }
+
+    #[test]
+    fn test_command_dispatch() -> crate::error::Result<()> {
+        let response = handle_line(r#"{"cmd":"start","id":"a"}"#, |command| {
+            assert_eq!(command, Command::Start);
+            Reply::Ok(Status {
+                running: true,
+                ..status()
+            })
+        });
+        assert!(response.ok);
+        assert_eq!(response.result.as_deref(), Some("ok"));
+        assert_eq!(response.status.map(|s| s.running), Some(true));
+
+        let line = handle_line(r#"{"cmd":"status"}"#, |_| Reply::Unchanged(status())).to_line();
+        assert!(line.ends_with('\n'));
+        let parsed: ServerResponse = serde_json::from_str(&line)?;
+        assert_eq!(parsed.result.as_deref(), Some("unchanged"));
+        assert_eq!(parsed.status, Some(status()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_subscribe_response_and_event_line() -> crate::error::Result<()> {
+        let response = handle_line(r#"{"cmd":"subscribe"}"#, |command| {
+            assert_eq!(command, Command::Status);
+            Reply::Unchanged(status())
+        });
+        assert!(response.is_subscription());
+        assert_eq!(response.status, Some(status()));
+
+        let event = TimerEvent::Transition {
+            transition: Transition::WorkComplete,
+            skipped: true,
+        };
+        let line = EventLine::new(event, status()).to_line();
+        assert!(line.contains(r#""event":"transition""#));
+        assert!(line.contains(r#""transition":"work_complete""#));
+        let parsed: EventLine = serde_json::from_str(&line)?;
+        assert_eq!(parsed.event, event);
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_codes() -> crate::error::Result<()> {
+        let code = |line: &str| {
+            handle_line(line, |_| {
+                Reply::Error(ErrorCode::Timeout, "slow".to_string())
+            })
+            .error
+            .map(|e| e.code)
+        };
+        assert_eq!(code("{not json"), Some(ErrorCode::InvalidRequest));
+        assert_eq!(code(r#"{"id":1}"#), Some(ErrorCode::InvalidRequest));
+        assert_eq!(
+            code(r#"{"cmd":"start","v":99}"#),
+            Some(ErrorCode::UnsupportedVersion)
+        );
+        assert_eq!(code(r#"{"cmd":"dance"}"#), Some(ErrorCode::UnknownCommand));
+        assert_eq!(
+            code(r#"{"cmd":"extend:soon"}"#),
+            Some(ErrorCode::InvalidArgument)
+        );
+        assert_eq!(code(r#"{"cmd":"start"}"#), Some(ErrorCode::Timeout));
+        Ok(())
+    }
+}
@@ -0,0 +1,461 @@
+//! Process-wide registry of timers shared between widgets
+//!
+//! A widget that names a `timer` in its config binds to the one running
+//! under that name, starting it from its own config if no other widget holds
+//! it yet. Everything that belongs to the timer rather than to a key (its
+//! control socket, saved state, history, hooks, notifications and sounds)
+//! lives in [`SharedTimer`], so it exists once however many keys show it.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard, PoisonError, Weak};
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::history;
+use crate::hooks::Hooks;
+use crate::notify::{Notification, Notifier};
+use crate::protocol::EventLine;
+use crate::socket::{self, Command, CommandError, Reply, Request, SocketListener, Status};
+use crate::sound;
+use crate::state;
+use crate::timer::{Stamp, Timer, TimerEvent};
+
+/// A handle on a timer, shared by every widget bound to it
+pub type TimerHandle = Arc<Mutex<SharedTimer>>;
+
+/// Named timers held by at least one widget
+static TIMERS: LazyLock<Mutex<HashMap<String, Weak<Mutex<SharedTimer>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Lock `mutex`, carrying on with its data if another widget panicked while holding it
+pub fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Bind to the timer `cfg` asks for
+///
+/// Widgets naming the same `timer` share it; the config of the first one to
+/// bind decides its schedule and behaviour. Without a name the widget gets a
+/// timer of its own.
+pub fn bind(cfg: &Config) -> TimerHandle {
+    match &cfg.timer {
+        Some(name) => bind_named(name, || SharedTimer::start(cfg, Some(name))),
+        None => Arc::new(Mutex::new(SharedTimer::start(cfg, cfg.instance.as_deref()))),
+    }
+}
+
+/// Let go of `handle`, closing its timer if nobody else holds it
+///
+/// Done under the registry lock, so a widget binding the same timer at the
+/// same moment either keeps it alive or starts a new one after it has closed.
+pub fn release(handle: TimerHandle) {
+    let _timers = lock(&TIMERS);
+    drop(handle);
+}
+
+/// Bind to the timer called `name`, calling `start` if nobody holds it yet
+fn bind_named<F>(name: &str, start: F) -> TimerHandle
+where
+    F: FnOnce() -> SharedTimer,
+{
+    let mut timers = lock(&TIMERS);
+    timers.retain(|_, timer| timer.strong_count() > 0);
+    if let Some(timer) = timers.get(name).and_then(Weak::upgrade) {
+        tracing::info!(timer = name, "Bound to shared timer");
+        return timer;
+    }
+
+    let timer = Arc::new(Mutex::new(start()));
+    timers.insert(name.to_string(), Arc::downgrade(&timer));
+    timer
+}
+
+/// A timer and everything that acts on its behalf
+pub struct SharedTimer {
+    pub timer: Timer,
+    // Sounds to play on phase transitions (keyed by schedule index)
+    // Sound indicates the STARTING phase, not the ending one
+    sounds: HashMap<usize, PathBuf>,
+    // Sound played once as a phase enters its final warning period
+    warning_sound: Option<PathBuf>,
+    // How long before the end of a phase the warning starts, from the first
+    // widget to bind
+    warn_before: Duration,
+    // Whether the current phase is already within its warning period
+    warned: bool,
+    // Phase transitions published so far, for keys animating them
+    transitions: u64,
+    // Shell commands run on timer events
+    hooks: Hooks,
+    // Desktop notifications on phase changes (None when disabled)
+    notifier: Option<Notifier>,
+    // Socket control
+    command_rx: Option<Receiver<Request>>,
+    socket_listener: Option<SocketListener>,
+    // Where timer state is persisted (None when persistence is disabled)
+    state_path: Option<PathBuf>,
+    // Where finished phases are logged (None when history is disabled)
+    history_path: Option<PathBuf>,
+}
+
+impl SharedTimer {
+    /// A timer with no socket, persistence, history or side effects
+    pub fn detached(cfg: &Config) -> Self {
+        SharedTimer {
+            timer: Timer::new(cfg),
+            sounds: HashMap::new(),
+            warning_sound: None,
+            warn_before: cfg.warn_before,
+            warned: false,
+            transitions: 0,
+            hooks: Hooks::default(),
+            notifier: None,
+            command_rx: None,
+            socket_listener: None,
+            state_path: None,
+            history_path: None,
+        }
+    }
+
+    /// Start a timer from `cfg`, restoring its saved state and listening on
+    /// the control socket for `instance`
+    pub fn start(cfg: &Config, instance: Option<&str>) -> Self {
+        let mut shared = Self::detached(cfg);
+
+        shared.history_path = history::get_history_path();
+        if shared.history_path.is_none() {
+            tracing::warn!("Failed to determine XDG data directory, history disabled");
+        }
+        shared.restore_state(cfg, instance);
+        // A discarded saved phase is logged as interrupted
+        shared.write_history();
+        shared.hooks = Hooks::new(cfg.hooks.clone(), Duration::from_secs(cfg.hook_timeout));
+
+        // Resolve per-phase sound paths
+        for (index, phase) in cfg.schedule.phases().iter().enumerate() {
+            if let Some(sound_name) = &phase.sound
+                && let Some(path) = sound::resolve_sound(sound_name)
+            {
+                tracing::info!(phase = phase.name, path = %path.display(), "Sound configured");
+                shared.sounds.insert(index, path);
+            }
+        }
+
+        shared.warning_sound = cfg.warning_sound.as_deref().and_then(sound::resolve_sound);
+        if let Some(path) = &shared.warning_sound {
+            tracing::info!(path = %path.display(), "Warning sound configured");
+        }
+
+        // Socket commands and notification actions share one command channel
+        let (tx, rx) = socket::command_channel();
+        shared.command_rx = Some(rx);
+        if cfg.notify {
+            shared.start_notifier(tx.clone());
+        }
+
+        // Start the socket listener for external control, one socket per timer
+        shared.start_socket_listener(instance, tx);
+        shared
+    }
+
+    fn start_notifier(&mut self, tx: Sender<Request>) {
+        match Notifier::connect(tx) {
+            Ok(notifier) => {
+                self.notifier = Some(notifier);
+                tracing::info!("Desktop notifications enabled");
+            }
+            Err(e) => {
+                tracing::warn!(
+                    error = %e,
+                    "Notification service unavailable, notifications disabled"
+                );
+            }
+        }
+    }
+
+    fn start_socket_listener(&mut self, instance: Option<&str>, tx: Sender<Request>) {
+        match SocketListener::new(instance, tx) {
+            Ok(listener) => {
+                self.socket_listener = Some(listener);
+                tracing::info!(instance, "Socket control enabled");
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to start socket listener, control disabled");
+            }
+        }
+    }
+
+    /// Restore persisted timer state according to the configured policy
+    fn restore_state(&mut self, cfg: &Config, instance: Option<&str>) {
+        self.state_path = state::get_state_path(instance);
+
+        let Some(path) = &self.state_path else {
+            tracing::warn!("Failed to determine XDG state directory, persistence disabled");
+            return;
+        };
+
+        if let Some(snapshot) = state::load(path) {
+            self.timer.restore(&snapshot, cfg.restore, Stamp::now());
+            tracing::info!(
+                policy = cfg.restore.as_ref(),
+                phase = self.timer.phase().name,
+                elapsed_secs = self.timer.elapsed_secs(),
+                running = self.timer.is_running(),
+                "Restored timer state"
+            );
+        }
+    }
+
+    /// Bring the timer up to date: apply pending commands, advance with the
+    /// clock, play sounds and publish events
+    ///
+    /// Every bound widget polls, but each change is only acted on once.
+    pub fn poll(&mut self) {
+        self.process_commands();
+
+        let was_overtime = self.timer.is_overtime();
+        let transitions = self.timer.update();
+
+        // Play sound on phase transition
+        // Sound indicates the phase that is STARTING, not the one that ended
+        if let Some(transition) = transitions.last() {
+            tracing::debug!(?transition, count = transitions.len(), "Phase transition");
+            if let Some(path) = self.sounds.get(&self.timer.phase_index()) {
+                sound::play_sound(path);
+            }
+            self.save_state();
+            // The new phase gets its own warning, however short it is
+            self.warned = false;
+        }
+
+        // Time is up but the work phase keeps counting: announce the next phase anyway
+        if !was_overtime
+            && self.timer.is_overtime()
+            && let Some(path) = self.sounds.get(&self.timer.next_phase_index())
+        {
+            sound::play_sound(path);
+        }
+
+        // Announce the end of the phase once, as it enters the warning period
+        let warning = self.is_warning();
+        if warning
+            && !self.warned
+            && let Some(path) = &self.warning_sound
+        {
+            sound::play_sound(path);
+        }
+        self.warned = warning;
+
+        self.publish_events();
+    }
+
+    /// True while the running phase is in its final `warn_before` stretch
+    ///
+    /// Every key showing the timer warns by this, so they blink in step with
+    /// the warning sound.
+    pub fn is_warning(&self) -> bool {
+        self.timer.is_running() && self.timer.ends_within(self.warn_before)
+    }
+
+    /// Phase transitions so far, however they came about
+    ///
+    /// Counts up on every transition, including a skip back to the same phase
+    /// of a one-phase schedule.
+    pub fn transitions(&self) -> u64 {
+        self.transitions
+    }
+
+    /// Apply a command from a key press
+    pub fn apply(&mut self, command: &Command) -> Result<bool, CommandError> {
+        let changed = self.run(command)?;
+        self.save_state();
+        Ok(changed)
+    }
+
+    /// Apply `command` to the timer, rearming the warning if it moved on to a phase
+    fn run(&mut self, command: &Command) -> Result<bool, CommandError> {
+        let phase = |timer: &Timer| (timer.phase_index(), timer.queued_transitions());
+        let before = phase(&self.timer);
+        let result = command.apply(&mut self.timer);
+        if phase(&self.timer) != before {
+            // The new phase gets its own warning, however short it is
+            self.warned = false;
+        }
+        result
+    }
+
+    fn process_commands(&mut self) {
+        let mut applied = false;
+        while let Some(request) = self.command_rx.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            tracing::debug!(command = ?request.command, "Processing command");
+            let reply = match self.run(&request.command) {
+                Ok(changed) => {
+                    applied |= changed;
+                    Reply::applied(changed, &self.timer)
+                }
+                Err(e) => Reply::from(e),
+            };
+            // The client may have given up waiting; nothing to do if so
+            let _ = request.reply.send(reply);
+        }
+
+        if applied {
+            self.save_state();
+        }
+    }
+
+    /// Run hooks for queued timer events and push them to socket subscribers
+    fn publish_events(&mut self) {
+        // Always drain so the queue cannot grow while nobody is listening
+        let events = self.timer.drain_events();
+        if events.is_empty() {
+            return;
+        }
+
+        self.transitions += events
+            .iter()
+            .filter(|event| matches!(event, TimerEvent::Transition { .. }))
+            .count() as u64;
+
+        let status = Status::from(&self.timer);
+        for &event in &events {
+            self.hooks.run(event, &status);
+        }
+
+        if let Some(notifier) = &self.notifier
+            && events
+                .iter()
+                .any(|event| matches!(event, TimerEvent::Transition { .. }))
+        {
+            notifier.notify(Notification::for_phase(&self.timer));
+        }
+
+        let Some(listener) = &self.socket_listener else {
+            return;
+        };
+        let broadcaster = listener.broadcaster();
+        if !broadcaster.has_subscribers() {
+            return;
+        }
+        for event in events {
+            broadcaster.publish(&EventLine::new(event, status.clone()).to_line());
+        }
+    }
+
+    /// Persist the timer state and log any phases that finished since the last save
+    fn save_state(&mut self) {
+        self.write_history();
+
+        let Some(path) = &self.state_path else {
+            return;
+        };
+
+        if let Err(e) = state::save(path, &self.timer.snapshot()) {
+            tracing::warn!(path = %path.display(), error = %e, "Failed to save timer state");
+        }
+    }
+
+    fn write_history(&mut self) {
+        // Always drain so the queue cannot grow while history is disabled
+        let entries = self.timer.drain_history();
+        let Some(path) = &self.history_path else {
+            return;
+        };
+
+        if let Err(e) = history::append(path, &entries) {
+            tracing::warn!(path = %path.display(), error = %e, "Failed to write history");
+        }
+    }
+}
+
+impl Drop for SharedTimer {
+    /// Save the state once the last widget lets go; the socket listener
+    /// stops and removes its socket as it is dropped
+    fn drop(&mut self) {
+        self.save_state();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigBuilder;
+
+    #[test]
+    fn test_named_timers_are_shared() -> crate::error::Result<()> {
+        let config = ConfigBuilder::default().build();
+        let start = || SharedTimer::detached(&config);
+        let first = bind_named("registry-test", start);
+        let second = bind_named("registry-test", || {
+            panic!("timer should be running already")
+        });
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(!Arc::ptr_eq(&first, &bind_named("registry-other", start)));
+
+        // A command through one handle shows through the other
+        lock(&first).timer.start();
+        assert!(lock(&second).timer.is_running());
+
+        // The registry lets go once the last widget does
+        release(first);
+        release(second);
+        assert!(
+            lock(&TIMERS)
+                .get("registry-test")
+                .is_none_or(|timer| timer.strong_count() == 0)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_commands_rearm_the_warning() -> crate::error::Result<()> {
+        let toml_str = r#"
+work = 1
+short_break = 1
+auto_start_break = true
+auto_start_work = true
+warn_before = 120
+"#;
+        let config = verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        let mut shared = SharedTimer::detached(&config);
+        shared.timer.start();
+        shared.poll();
+        assert!(shared.warned);
+
+        // A skip from a key press moves on to a phase shorter than the warning
+        assert!(shared.apply(&Command::Skip).is_ok());
+        assert!(!shared.warned);
+        shared.poll();
+        assert!(shared.warned);
+
+        // And so does one from the socket
+        let (tx, rx) = socket::command_channel();
+        shared.command_rx = Some(rx);
+        let (reply, _replies) = std::sync::mpsc::channel();
+        let request = Request {
+            command: Command::Skip,
+            reply,
+        };
+        assert!(tx.send(request).is_ok());
+        shared.process_commands();
+        assert!(!shared.warned);
+        Ok(())
+    }
+
+    #[test]
+    fn test_transitions_are_counted() -> crate::error::Result<()> {
+        let config = ConfigBuilder::default().build();
+        let mut shared = SharedTimer::detached(&config);
+        shared.poll();
+        assert_eq!(shared.transitions(), 0);
+
+        // A skip counts once its event is published
+        shared.timer.start();
+        shared.timer.skip();
+        shared.poll();
+        assert_eq!(shared.transitions(), 1);
+        Ok(())
+    }
+}
@@ -1,10 +1,13 @@
-use std::collections::HashMap;
+use std::ops::Range;
+use std::time::{SystemTime, UNIX_EPOCH};
 
+use chrono::{Local, Timelike};
 use strum::{AsRefStr, EnumString, VariantNames};
 use verandah_plugin::api::prelude::*;
 use verandah_plugin::utils::prelude::*;
 
-use crate::timer::{Phase, Timer};
+use crate::gradient::{mix, mix_channel};
+use crate::timer::{GoalProgress, Timer};
 
 /// Render mode for the timer display
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, AsRefStr, VariantNames)]
@@ -19,6 +22,42 @@ pub enum RenderMode {
     FillIcon,
     /// Icon starts green (unripe) and gradually returns to original colors as timer progresses
     Ripen,
+    /// Pie or ring around the time that drains as the phase runs out
+    Radial,
+    /// Analog dial with the rest of the phase shaded from the minute hand onwards
+    ClockFace,
+    /// Minutes only (seconds in the last minute) in seven-segment digits filling the key
+    BigDigits,
+}
+
+/// What a key shows of the timer it is bound to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, AsRefStr, VariantNames)]
+#[strum(serialize_all = "snake_case")]
+pub enum Role {
+    /// The timer itself, drawn in the configured render mode
+    #[default]
+    Countdown,
+    /// Work phases completed today, against the daily goal if one is set
+    Today,
+    /// The phase a skip would move on to; pressing it skips
+    Skip,
+    /// One slice of a progress bar spanning several keys in a row
+    Slice,
+}
+
+/// Which part of a row-wide progress bar a `slice` key draws
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Slice {
+    /// Position of the key in the row, from 0 on the left
+    pub index: u32,
+    /// Number of keys the bar spans
+    pub count: u32,
+}
+
+impl Default for Slice {
+    fn default() -> Self {
+        Slice { index: 0, count: 1 }
+    }
 }
 
 /// Fill direction for fill modes
@@ -32,6 +71,67 @@ pub enum FillDirection {
     FullToEmpty,
 }
 
+/// Which way the radial countdown sweeps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, AsRefStr, VariantNames)]
+#[strum(serialize_all = "snake_case")]
+pub enum RadialDirection {
+    /// Drain clockwise, like a clock hand
+    #[default]
+    Clockwise,
+    /// Drain counterclockwise
+    Counterclockwise,
+}
+
+/// Shape of the `radial` render mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RadialStyle {
+    /// Ring width in pixels; 0 draws a full pie behind the time
+    pub thickness: u32,
+    /// Where the countdown starts, in degrees clockwise from 12 o'clock
+    pub start_angle: f32,
+    /// Which way the remaining arc drains
+    pub direction: RadialDirection,
+    /// Smooth the arc edges by supersampling
+    pub antialias: bool,
+}
+
+impl Default for RadialStyle {
+    fn default() -> Self {
+        RadialStyle {
+            thickness: 8,
+            start_angle: 0.0,
+            direction: RadialDirection::default(),
+            antialias: true,
+        }
+    }
+}
+
+/// How a key shows that its phase is about to end
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, AsRefStr, VariantNames)]
+#[strum(serialize_all = "snake_case")]
+pub enum WarnStyle {
+    /// Dim the whole key every other half second
+    #[default]
+    Blink,
+    /// Wash the key in the `warning` color every other half second
+    Flash,
+}
+
+/// Animation shown when a key moves on to a new phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, AsRefStr, VariantNames)]
+#[strum(serialize_all = "snake_case")]
+pub enum TransitionStyle {
+    /// Repaint straight away
+    #[default]
+    Off,
+    /// Flash to the foreground color and back out into the new phase
+    Flash,
+    /// Sweep the new phase in from the left
+    Wipe,
+    /// Fade from the old phase into the new one
+    Crossfade,
+}
+
 /// When to display the phase indicator (work, short brk, long brk)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, AsRefStr, VariantNames)]
 #[strum(serialize_all = "snake_case")]
@@ -66,51 +166,102 @@ pub fn render_button(
     width: u32,
     height: u32,
     fg_color: Rgba<u8>,
-    work_bg: Rgba<u8>,
-    break_bg: Rgba<u8>,
+    phase_bg: Rgba<u8>,
     paused_bg: Rgba<u8>,
     empty_bg: Rgba<u8>,
     dot_running: Rgba<u8>,
     dot_paused: Rgba<u8>,
+    goal_color: Rgba<u8>,
     padding: f32,
     paused_icon: Option<&PluginImage>,
     phase_icon: Option<&PluginImage>,
     fallback_text: Option<&str>,
     paused_text: &str,
-    phases: &HashMap<String, String>,
     render_mode: RenderMode,
     fill_direction: FillDirection,
+    radial: RadialStyle,
     phase_indicator_display: PhaseIndicatorDisplay,
     pulse_on_pause: bool,
 ) -> RgbImage {
+    let goal = timer.goal_progress();
+    if let Some(goal) = goal
+        && goal.celebrating
+    {
+        return render_goal_reached(goal, width, height, fg_color, goal_color, padding);
+    }
+
     // At phase boundary (elapsed=0) and not running: show icon or fallback
-    if !timer.is_running() && timer.at_phase_boundary() {
-        if let Some(icon) = paused_icon {
-            return render_icon_with_dots(
-                icon,
-                width,
-                height,
-                display_iterations(timer),
-                dot_paused,
-            );
-        }
-        if let Some(text) = fallback_text {
-            return render_paused_text(text, width, height, fg_color, paused_bg, padding);
-        }
+    let paused_at_boundary = !timer.is_running() && timer.at_phase_boundary();
+    let rgb = if paused_at_boundary && let Some(icon) = paused_icon {
+        render_icon_with_dots(
+            icon,
+            width,
+            height,
+            display_iterations(timer),
+            timer.works_per_cycle(),
+            dot_paused,
+        )
+    } else if paused_at_boundary && let Some(text) = fallback_text {
+        render_paused_text(text, width, height, fg_color, paused_bg, padding)
+    } else {
+        render_mode_image(
+            timer,
+            width,
+            height,
+            fg_color,
+            phase_bg,
+            paused_bg,
+            empty_bg,
+            dot_running,
+            dot_paused,
+            padding,
+            phase_icon,
+            paused_text,
+            render_mode,
+            fill_direction,
+            radial,
+            phase_indicator_display,
+            pulse_on_pause,
+        )
+    };
+
+    match goal {
+        Some(goal) => render_goal_bar(&rgb, goal, goal_color, empty_bg),
+        None => rgb,
     }
+}
 
+/// Render the timer in the configured render mode
+#[allow(clippy::too_many_arguments)]
+fn render_mode_image(
+    timer: &Timer,
+    width: u32,
+    height: u32,
+    fg_color: Rgba<u8>,
+    phase_bg: Rgba<u8>,
+    paused_bg: Rgba<u8>,
+    empty_bg: Rgba<u8>,
+    dot_running: Rgba<u8>,
+    dot_paused: Rgba<u8>,
+    padding: f32,
+    phase_icon: Option<&PluginImage>,
+    paused_text: &str,
+    render_mode: RenderMode,
+    fill_direction: FillDirection,
+    radial: RadialStyle,
+    phase_indicator_display: PhaseIndicatorDisplay,
+    pulse_on_pause: bool,
+) -> RgbImage {
     match render_mode {
         RenderMode::Text => render_text_mode(
             timer,
             width,
             height,
             fg_color,
-            work_bg,
-            break_bg,
+            phase_bg,
             paused_bg,
             padding,
             paused_text,
-            phases,
             phase_indicator_display,
             dot_running,
             dot_paused,
@@ -120,10 +271,8 @@ pub fn render_button(
             width,
             height,
             fg_color,
-            work_bg,
-            break_bg,
+            phase_bg,
             empty_bg,
-            phases,
             fill_direction,
             paused_text,
             phase_indicator_display,
@@ -136,11 +285,9 @@ pub fn render_button(
             width,
             height,
             fg_color,
-            work_bg,
-            break_bg,
+            phase_bg,
             empty_bg,
             phase_icon,
-            phases,
             fill_direction,
             paused_text,
             phase_indicator_display,
@@ -154,29 +301,58 @@ pub fn render_button(
             height,
             fg_color,
             phase_icon,
-            phases,
             paused_text,
             phase_indicator_display,
             pulse_on_pause,
             dot_running,
             dot_paused,
         ),
+        RenderMode::Radial => render_radial_mode(
+            timer,
+            width,
+            height,
+            fg_color,
+            phase_bg,
+            empty_bg,
+            padding,
+            radial,
+            paused_text,
+            phase_indicator_display,
+            pulse_on_pause,
+            dot_running,
+            dot_paused,
+        ),
+        RenderMode::ClockFace => render_clock_face_mode(
+            timer,
+            width,
+            height,
+            fg_color,
+            phase_bg,
+            empty_bg,
+            paused_text,
+            phase_indicator_display,
+            pulse_on_pause,
+            dot_running,
+            dot_paused,
+        ),
+        RenderMode::BigDigits => render_big_digits_mode(
+            timer,
+            width,
+            height,
+            fg_color,
+            phase_bg,
+            paused_bg,
+            phase_indicator_display,
+            pulse_on_pause,
+            dot_running,
+            dot_paused,
+        ),
     }
 }
 
-/// Get the phase indicator text from the phases map
-fn get_phase_indicator<'a>(timer: &Timer, phases: &'a HashMap<String, String>) -> &'a str {
-    match timer.phase() {
-        Phase::Work => phases.get("work").map(|s| s.as_str()).unwrap_or("work"),
-        Phase::ShortBreak => phases
-            .get("short_break")
-            .map(|s| s.as_str())
-            .unwrap_or("short brk"),
-        Phase::LongBreak => phases
-            .get("long_break")
-            .map(|s| s.as_str())
-            .unwrap_or("long brk"),
-    }
+/// Get the phase indicator text for the current phase
+fn get_phase_indicator(timer: &Timer) -> &str {
+    &timer.phase().indicator
 }
 
 /// Configuration for the common overlay elements
@@ -190,12 +366,15 @@ struct OverlayConfig<'a> {
     phase_indicator_display: PhaseIndicatorDisplay,
     /// Text to show centered when paused (e.g., "||")
     paused_text: Option<&'a str>,
+    /// Show the time counted so far at the top of a running count-up phase
+    show_count_up: bool,
 }
 
 /// Render common overlay elements (top/bottom indicators, paused text)
 ///
 /// This handles:
-/// - Top indicator: remaining time when paused mid-interval, phase indicator when configured
+/// - Top indicator: remaining time when paused mid-interval or counting up, phase indicator
+///   when configured
 /// - Bottom indicator: phase indicator when paused mid-interval and configured, dots otherwise
 /// - Centered paused text when not running (if provided)
 fn render_overlay(rgba: &mut RgbaImage, timer: &Timer, config: &OverlayConfig) {
@@ -211,7 +390,9 @@ fn render_overlay(rgba: &mut RgbaImage, timer: &Timer, config: &OverlayConfig) {
     };
 
     // Top indicator: remaining time when paused mid-interval, phase indicator when configured
-    if is_paused_mid_interval {
+    // A count-up phase has no end for the fill to show, so its time stays on top while running
+    let show_count_up = config.show_count_up && is_running && timer.counts_up();
+    if is_paused_mid_interval || show_count_up {
         // Remaining time at top (24px, 4px top margin)
         draw_text_hcentered(rgba, &timer.remaining_formatted(), config.fg_color, 24.0, 4);
     } else if show_phase && let Some(phase) = config.phase_indicator {
@@ -245,12 +426,10 @@ fn render_text_mode(
     width: u32,
     height: u32,
     fg_color: Rgba<u8>,
-    work_bg: Rgba<u8>,
-    break_bg: Rgba<u8>,
+    phase_bg: Rgba<u8>,
     paused_bg: Rgba<u8>,
     padding: f32,
     paused_text: &str,
-    phases: &HashMap<String, String>,
     phase_indicator_display: PhaseIndicatorDisplay,
     dot_running: Rgba<u8>,
     dot_paused: Rgba<u8>,
@@ -260,10 +439,8 @@ fn render_text_mode(
     // Determine background color based on state
     let bg = if !timer.is_running() {
         paused_bg
-    } else if timer.phase().is_break() {
-        break_bg
     } else {
-        work_bg
+        phase_bg
     };
 
     // Fill background
@@ -279,7 +456,7 @@ fn render_text_mode(
 
     // Render common overlay elements (top/bottom indicators)
     // paused_text is None because the centered text above already serves that purpose
-    let phase_indicator = get_phase_indicator(timer, phases);
+    let phase_indicator = get_phase_indicator(timer);
     render_overlay(
         &mut rgba,
         timer,
@@ -290,6 +467,7 @@ fn render_text_mode(
             phase_indicator: Some(phase_indicator),
             phase_indicator_display,
             paused_text: None,
+            show_count_up: false,
         },
     );
 
@@ -303,10 +481,8 @@ fn render_fill_bg_mode(
     width: u32,
     height: u32,
     fg_color: Rgba<u8>,
-    work_bg: Rgba<u8>,
-    break_bg: Rgba<u8>,
+    phase_bg: Rgba<u8>,
     empty_bg: Rgba<u8>,
-    phases: &HashMap<String, String>,
     fill_direction: FillDirection,
     paused_text: &str,
     phase_indicator_display: PhaseIndicatorDisplay,
@@ -319,13 +495,6 @@ fn render_fill_bg_mode(
     // Fill with empty_bg as the base/unfilled color
     draw_filled_rect_mut(&mut rgba, Rect::at(0, 0).of_size(width, height), empty_bg);
 
-    // Determine the fill color based on current phase
-    let fill_color = if timer.phase().is_break() {
-        break_bg
-    } else {
-        work_bg
-    };
-
     // Calculate progress and fill height
     let progress = timer.progress_ratio();
     let fill_height = (height as f32 * progress) as u32;
@@ -338,7 +507,7 @@ fn render_fill_bg_mode(
                 draw_filled_rect_mut(
                     &mut rgba,
                     Rect::at(0, y_start as i32).of_size(width, fill_height),
-                    fill_color,
+                    phase_bg,
                 );
             }
             FillDirection::FullToEmpty => {
@@ -349,14 +518,14 @@ fn render_fill_bg_mode(
                     draw_filled_rect_mut(
                         &mut rgba,
                         Rect::at(0, 0).of_size(width, remaining_height),
-                        fill_color,
+                        phase_bg,
                     );
                 }
             }
         }
     } else if matches!(fill_direction, FillDirection::FullToEmpty) {
         // At start (progress=0), full_to_empty should show full fill
-        draw_filled_rect_mut(&mut rgba, Rect::at(0, 0).of_size(width, height), fill_color);
+        draw_filled_rect_mut(&mut rgba, Rect::at(0, 0).of_size(width, height), phase_bg);
     }
 
     // Apply brightness pulse before overlay (if paused and enabled)
@@ -365,7 +534,7 @@ fn render_fill_bg_mode(
     }
 
     // Render common overlay elements
-    let phase_indicator = get_phase_indicator(timer, phases);
+    let phase_indicator = get_phase_indicator(timer);
     render_overlay(
         &mut rgba,
         timer,
@@ -376,6 +545,7 @@ fn render_fill_bg_mode(
             phase_indicator: Some(phase_indicator),
             phase_indicator_display,
             paused_text: Some(paused_text),
+            show_count_up: true,
         },
     );
 
@@ -390,11 +560,9 @@ fn render_fill_icon_mode(
     width: u32,
     height: u32,
     fg_color: Rgba<u8>,
-    work_bg: Rgba<u8>,
-    break_bg: Rgba<u8>,
+    phase_bg: Rgba<u8>,
     empty_bg: Rgba<u8>,
     phase_icon: Option<&PluginImage>,
-    phases: &HashMap<String, String>,
     fill_direction: FillDirection,
     paused_text: &str,
     phase_indicator_display: PhaseIndicatorDisplay,
@@ -416,10 +584,8 @@ fn render_fill_icon_mode(
             width,
             height,
             fg_color,
-            work_bg,
-            break_bg,
+            phase_bg,
             empty_bg,
-            phases,
             fill_direction,
             paused_text,
             phase_indicator_display,
@@ -472,7 +638,380 @@ fn render_fill_icon_mode(
     }
 
     // Render common overlay elements
-    let phase_indicator = get_phase_indicator(timer, phases);
+    let phase_indicator = get_phase_indicator(timer);
+    render_overlay(
+        &mut rgba,
+        timer,
+        &OverlayConfig {
+            fg_color,
+            dot_running,
+            dot_paused,
+            phase_indicator: Some(phase_indicator),
+            phase_indicator_display,
+            paused_text: Some(paused_text),
+            show_count_up: true,
+        },
+    );
+
+    rgba_to_rgb(&rgba)
+}
+
+/// Render radial mode: a pie or ring behind the time that drains as the phase runs out
+#[allow(clippy::too_many_arguments)]
+fn render_radial_mode(
+    timer: &Timer,
+    width: u32,
+    height: u32,
+    fg_color: Rgba<u8>,
+    phase_bg: Rgba<u8>,
+    empty_bg: Rgba<u8>,
+    padding: f32,
+    radial: RadialStyle,
+    paused_text: &str,
+    phase_indicator_display: PhaseIndicatorDisplay,
+    pulse_on_pause: bool,
+    dot_running: Rgba<u8>,
+    dot_paused: Rgba<u8>,
+) -> RgbImage {
+    use std::f32::consts::TAU;
+
+    let mut rgba = RgbaImage::new(width, height);
+
+    let cx = width as f32 / 2.0;
+    let cy = height as f32 / 2.0;
+    let outer = cx.min(cy) - 1.0;
+    let inner = match radial.thickness {
+        0 => 0.0,
+        thickness => (outer - thickness as f32).max(0.0),
+    };
+    // The arc from the start angle up to here has drained away
+    let drained = timer.progress_ratio().clamp(0.0, 1.0) * TAU;
+    let start = radial.start_angle.to_radians();
+
+    let in_arc = |x: f32, y: f32| {
+        let (dx, dy) = (x - cx, y - cy);
+        let distance = (dx * dx + dy * dy).sqrt();
+        if !(inner..=outer).contains(&distance) {
+            return false;
+        }
+        let clockwise = clockwise_angle(dx, dy) - start;
+        let angle = match radial.direction {
+            RadialDirection::Clockwise => clockwise,
+            RadialDirection::Counterclockwise => -clockwise,
+        };
+        angle.rem_euclid(TAU) >= drained
+    };
+
+    // Sample each pixel on a grid when anti-aliasing, at its center otherwise
+    let samples = if radial.antialias { SUPERSAMPLES } else { 1 };
+    for y in 0..height {
+        for x in 0..width {
+            let covered = coverage(x, y, samples, in_arc);
+            rgba.put_pixel(x, y, mix(empty_bg, phase_bg, covered));
+        }
+    }
+
+    // Apply brightness pulse before overlay (if paused and enabled)
+    if !timer.is_running() && pulse_on_pause {
+        apply_brightness_pulse(&mut rgba);
+    }
+
+    // The time sits in the middle of the ring while running; the overlay
+    // shows it at the top next to the paused text otherwise
+    if timer.is_running() {
+        draw_centered_text_with_reserved(
+            &mut rgba,
+            &timer.remaining_formatted(),
+            fg_color,
+            padding,
+            18.0,
+            18.0,
+            0.0,
+        );
+    }
+
+    // Render common overlay elements
+    let phase_indicator = get_phase_indicator(timer);
+    render_overlay(
+        &mut rgba,
+        timer,
+        &OverlayConfig {
+            fg_color,
+            dot_running,
+            dot_paused,
+            phase_indicator: Some(phase_indicator),
+            phase_indicator_display,
+            paused_text: Some(paused_text),
+            show_count_up: false,
+        },
+    );
+
+    rgba_to_rgb(&rgba)
+}
+
+/// Segments lit for each digit, bit 0 to 6 being segments a to g
+///
+/// ```text
+///  aaa
+/// f   b
+///  ggg
+/// e   c
+///  ddd
+/// ```
+const SEVEN_SEGMENT_DIGITS: [u8; 10] = [0x3F, 0x06, 0x5B, 0x4F, 0x66, 0x6D, 0x7D, 0x07, 0x7F, 0x6F];
+
+/// Render big-digits mode: whole minutes left in seven-segment digits that
+/// fill the key, switching to seconds behind a colon in the final minute
+#[allow(clippy::too_many_arguments)]
+fn render_big_digits_mode(
+    timer: &Timer,
+    width: u32,
+    height: u32,
+    fg_color: Rgba<u8>,
+    phase_bg: Rgba<u8>,
+    paused_bg: Rgba<u8>,
+    phase_indicator_display: PhaseIndicatorDisplay,
+    pulse_on_pause: bool,
+    dot_running: Rgba<u8>,
+    dot_paused: Rgba<u8>,
+) -> RgbImage {
+    let mut rgba = RgbaImage::new(width, height);
+
+    let bg = if timer.is_running() {
+        phase_bg
+    } else {
+        paused_bg
+    };
+    draw_filled_rect_mut(&mut rgba, Rect::at(0, 0).of_size(width, height), bg);
+
+    let secs = if timer.is_overtime() {
+        timer.overtime_secs()
+    } else if timer.counts_up() {
+        timer.elapsed_secs()
+    } else {
+        timer.remaining_secs()
+    };
+    let digits = if secs >= 60 {
+        (secs / 60).to_string()
+    } else {
+        // The colon tells seconds apart from minutes
+        format!(":{secs:02}")
+    };
+
+    // Leave room for the overlay: the time shown on top while paused
+    // mid-phase, dots or phase indicator at the bottom
+    let paused_mid_interval = !timer.is_running() && !timer.at_phase_boundary();
+    let top = if paused_mid_interval { 30 } else { 18 };
+    let bottom = 22;
+    let margin = 4;
+    let area_w = width.saturating_sub(2 * margin as u32);
+    let area_h = height.saturating_sub(top as u32 + bottom);
+    if area_w > 0 && area_h > 0 {
+        let area = Rect::at(margin, top).of_size(area_w, area_h);
+        draw_seven_segment(&mut rgba, &digits, area, fg_color);
+    }
+
+    // Apply brightness pulse before overlay (if paused and enabled)
+    if !timer.is_running() && pulse_on_pause {
+        apply_brightness_pulse(&mut rgba);
+    }
+
+    // The digits already show the time, so no paused text on top of them
+    let phase_indicator = get_phase_indicator(timer);
+    render_overlay(
+        &mut rgba,
+        timer,
+        &OverlayConfig {
+            fg_color,
+            dot_running,
+            dot_paused,
+            phase_indicator: Some(phase_indicator),
+            phase_indicator_display,
+            paused_text: None,
+            show_count_up: false,
+        },
+    );
+
+    rgba_to_rgb(&rgba)
+}
+
+/// Draw `digits` as seven-segment digits, as large as fit centered in `area`
+///
+/// A `:` is drawn as a colon a little under half a digit wide.
+fn draw_seven_segment(rgba: &mut RgbaImage, digits: &str, area: Rect, color: Rgba<u8>) {
+    let count = digits.chars().count() as f32;
+    if count == 0.0 {
+        return;
+    }
+
+    // Width of each character as a share of a digit's
+    let share = |c: char| if c == ':' { 0.4 } else { 1.0 };
+    let shares: f32 = digits.chars().map(share).sum();
+
+    // Digits are half as wide as they are tall, a segment's thickness apart
+    let mut digit_h = area.height() as f32;
+    let mut digit_w = digit_h / 2.0;
+    let total_w = |digit_w: f32| shares * digit_w + (count - 1.0) * digit_w / 5.0;
+    if total_w(digit_w) > area.width() as f32 {
+        digit_w *= area.width() as f32 / total_w(digit_w);
+        digit_h = digit_w * 2.0;
+    }
+    let thickness = (digit_w / 5.0).max(2.0);
+    let half = digit_h / 2.0;
+
+    let mut x = area.left() as f32 + (area.width() as f32 - total_w(digit_w)) / 2.0;
+    let y = area.top() as f32 + (area.height() as f32 - digit_h) / 2.0;
+    let fill = |rgba: &mut RgbaImage, (sx, sy, sw, sh): (f32, f32, f32, f32)| {
+        draw_filled_rect_mut(
+            rgba,
+            Rect::at(sx.round() as i32, sy.round() as i32)
+                .of_size((sw.round() as u32).max(1), (sh.round() as u32).max(1)),
+            color,
+        );
+    };
+    for c in digits.chars() {
+        let glyph_w = digit_w * share(c);
+        if c == ':' {
+            // Two square dots, a third of the way from the top and the bottom
+            let dot_x = x + (glyph_w - thickness) / 2.0;
+            for dot_y in [y + digit_h / 3.0, y + digit_h * 2.0 / 3.0] {
+                fill(rgba, (dot_x, dot_y - thickness / 2.0, thickness, thickness));
+            }
+        } else if let Some(digit) = c.to_digit(10) {
+            let right = x + digit_w - thickness;
+            // Segments a to g as (x, y, width, height)
+            let segments = [
+                (x, y, digit_w, thickness),
+                (right, y, thickness, half),
+                (right, y + half, thickness, half),
+                (x, y + digit_h - thickness, digit_w, thickness),
+                (x, y + half, thickness, half),
+                (x, y, thickness, half),
+                (x, y + half - thickness / 2.0, digit_w, thickness),
+            ];
+            let lit = SEVEN_SEGMENT_DIGITS[digit as usize];
+            for (segment, &rect) in segments.iter().enumerate() {
+                if lit & (1 << segment) != 0 {
+                    fill(rgba, rect);
+                }
+            }
+        }
+        x += glyph_w + digit_w / 5.0;
+    }
+}
+
+/// Samples per pixel side when smoothing shapes drawn by [`coverage`]
+const SUPERSAMPLES: u32 = 4;
+
+/// Share of pixel (`x`, `y`) inside a shape, sampled on a `samples`² grid
+fn coverage<F>(x: u32, y: u32, samples: u32, inside: F) -> f32
+where
+    F: Fn(f32, f32) -> bool,
+{
+    let step = 1.0 / samples as f32;
+    let mut hits = 0;
+    for sy in 0..samples {
+        for sx in 0..samples {
+            let px = x as f32 + (sx as f32 + 0.5) * step;
+            let py = y as f32 + (sy as f32 + 0.5) * step;
+            hits += u32::from(inside(px, py));
+        }
+    }
+    hits as f32 / (samples * samples) as f32
+}
+
+/// Angle of offset (`dx`, `dy`) in radians clockwise from 12 o'clock (y grows downwards)
+fn clockwise_angle(dx: f32, dy: f32) -> f32 {
+    dx.atan2(-dy).rem_euclid(std::f32::consts::TAU)
+}
+
+/// Distance from point `p` to the segment from `a` to `b`
+fn distance_to_segment(p: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (abx, aby) = (b.0 - a.0, b.1 - a.1);
+    let (apx, apy) = (p.0 - a.0, p.1 - a.1);
+    let length_sq = abx * abx + aby * aby;
+    let t = if length_sq > 0.0 {
+        ((apx * abx + apy * aby) / length_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (dx, dy) = (apx - abx * t, apy - aby * t);
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// Render clock-face mode: an analog dial with the rest of the phase shaded
+/// from the minute hand round to the time it ends
+#[allow(clippy::too_many_arguments)]
+fn render_clock_face_mode(
+    timer: &Timer,
+    width: u32,
+    height: u32,
+    fg_color: Rgba<u8>,
+    phase_bg: Rgba<u8>,
+    empty_bg: Rgba<u8>,
+    paused_text: &str,
+    phase_indicator_display: PhaseIndicatorDisplay,
+    pulse_on_pause: bool,
+    dot_running: Rgba<u8>,
+    dot_paused: Rgba<u8>,
+) -> RgbImage {
+    use std::f32::consts::TAU;
+
+    let mut rgba = RgbaImage::new(width, height);
+
+    let cx = width as f32 / 2.0;
+    let cy = height as f32 / 2.0;
+    let radius = cx.min(cy) - 1.0;
+
+    // The dial shows the wall clock; the wedge covers what is left of the
+    // phase, up to a full hour
+    let now = Local::now();
+    let minutes = now.minute() as f32 + now.second() as f32 / 60.0;
+    let hours = (now.hour() % 12) as f32 + minutes / 60.0;
+    let minute_angle = minutes / 60.0 * TAU;
+    let hour_angle = hours / 12.0 * TAU;
+    let wedge = (timer.remaining_secs() as f32 / 3600.0).min(1.0) * TAU;
+
+    let hand_tip = |angle: f32, length: f32| (cx + angle.sin() * length, cy - angle.cos() * length);
+    let minute_tip = hand_tip(minute_angle, radius * 0.85);
+    let hour_tip = hand_tip(hour_angle, radius * 0.55);
+
+    let in_wedge = |x: f32, y: f32| {
+        let (dx, dy) = (x - cx, y - cy);
+        (dx * dx + dy * dy).sqrt() <= radius
+            && (clockwise_angle(dx, dy) - minute_angle).rem_euclid(TAU) < wedge
+    };
+    // One tick every five minutes, just inside the rim
+    let on_tick = |x: f32, y: f32| {
+        let (dx, dy) = (x - cx, y - cy);
+        let distance = (dx * dx + dy * dy).sqrt();
+        if !(radius * 0.8..=radius).contains(&distance) {
+            return false;
+        }
+        let from_tick = (clockwise_angle(dx, dy) / TAU * 12.0).fract();
+        from_tick.min(1.0 - from_tick) / 12.0 * TAU * distance < 1.0
+    };
+    let on_hands = |x: f32, y: f32| {
+        distance_to_segment((x, y), (cx, cy), minute_tip) < 1.0
+            || distance_to_segment((x, y), (cx, cy), hour_tip) < 1.5
+    };
+
+    for y in 0..height {
+        for x in 0..width {
+            let mut color = mix(empty_bg, phase_bg, coverage(x, y, SUPERSAMPLES, in_wedge));
+            color = mix(color, fg_color, coverage(x, y, SUPERSAMPLES, on_tick));
+            color = mix(color, fg_color, coverage(x, y, SUPERSAMPLES, on_hands));
+            rgba.put_pixel(x, y, color);
+        }
+    }
+
+    // Apply brightness pulse before overlay (if paused and enabled)
+    if !timer.is_running() && pulse_on_pause {
+        apply_brightness_pulse(&mut rgba);
+    }
+
+    // Render common overlay elements
+    let phase_indicator = get_phase_indicator(timer);
     render_overlay(
         &mut rgba,
         timer,
@@ -483,12 +1022,73 @@ fn render_fill_icon_mode(
             phase_indicator: Some(phase_indicator),
             phase_indicator_display,
             paused_text: Some(paused_text),
+            show_count_up: true,
         },
     );
 
     rgba_to_rgb(&rgba)
 }
 
+/// Whether a blinking warning is in its visible half at `now`
+pub fn warning_blink_on(now: SystemTime) -> bool {
+    now.duration_since(UNIX_EPOCH)
+        .is_ok_and(|since| since.as_millis() / 500 % 2 == 0)
+}
+
+/// Blink or flash a rendered key in the visible half of the warning cycle
+pub fn apply_warning(rgb: &mut RgbImage, style: WarnStyle, warning_color: Rgba<u8>, on: bool) {
+    if !on {
+        return;
+    }
+    for pixel in rgb.pixels_mut() {
+        for channel in 0..3 {
+            let value = pixel[channel] as f32;
+            pixel[channel] = match style {
+                WarnStyle::Blink => value * 0.3,
+                WarnStyle::Flash => value + (warning_color[channel] as f32 - value) * 0.75,
+            }
+            .round() as u8;
+        }
+    }
+}
+
+/// Interpolate a frame `t` of the way (0.0 to 1.0) through a transition
+/// from the previous phase's frame to the new one
+pub fn transition_frame(
+    from: &RgbImage,
+    to: &RgbImage,
+    style: TransitionStyle,
+    flash_color: Rgba<u8>,
+    t: f32,
+) -> RgbImage {
+    // The key was resized in between; nothing sensible to blend
+    if from.dimensions() != to.dimensions() {
+        return to.clone();
+    }
+
+    let t = t.clamp(0.0, 1.0);
+    let wiped = t * to.width() as f32;
+    let mut frame = to.clone();
+    for (x, y, pixel) in frame.enumerate_pixels_mut() {
+        let old = from.get_pixel(x, y);
+        for channel in 0..3 {
+            let new = pixel[channel];
+            pixel[channel] = match style {
+                TransitionStyle::Off => new,
+                TransitionStyle::Crossfade => mix_channel(old[channel], new, t),
+                TransitionStyle::Wipe if (x as f32) < wiped => new,
+                TransitionStyle::Wipe => old[channel],
+                // Out to the flash color in the first half, into the new frame in the second
+                TransitionStyle::Flash if t < 0.5 => {
+                    mix_channel(old[channel], flash_color[channel], t * 2.0)
+                }
+                TransitionStyle::Flash => mix_channel(flash_color[channel], new, t * 2.0 - 1.0),
+            };
+        }
+    }
+    frame
+}
+
 /// Render fallback text when paused at phase boundary and no icon is available
 fn render_paused_text(
     text: &str,
@@ -506,6 +1106,206 @@ fn render_paused_text(
     rgba_to_rgb(&rgba)
 }
 
+/// Height of the daily goal bar along the bottom edge, below the iteration dots
+const GOAL_BAR_PX: u32 = 3;
+
+/// Draw the daily goal progress as a thin bar along the bottom edge
+fn render_goal_bar(
+    rgb: &RgbImage,
+    goal: GoalProgress,
+    goal_color: Rgba<u8>,
+    track_color: Rgba<u8>,
+) -> RgbImage {
+    let mut rgba = rgb_to_rgba(rgb);
+    let (width, height) = rgba.dimensions();
+    let y = height.saturating_sub(GOAL_BAR_PX) as i32;
+
+    draw_filled_rect_mut(
+        &mut rgba,
+        Rect::at(0, y).of_size(width, GOAL_BAR_PX),
+        track_color,
+    );
+    let filled = (width as f32 * goal.ratio()).round() as u32;
+    if filled > 0 {
+        draw_filled_rect_mut(
+            &mut rgba,
+            Rect::at(0, y).of_size(filled, GOAL_BAR_PX),
+            goal_color,
+        );
+    }
+
+    rgba_to_rgb(&rgba)
+}
+
+/// Render the celebration shown for a while after the daily goal is reached
+fn render_goal_reached(
+    goal: GoalProgress,
+    width: u32,
+    height: u32,
+    fg_color: Rgba<u8>,
+    goal_color: Rgba<u8>,
+    padding: f32,
+) -> RgbImage {
+    let text = format!("Goal!\n{}/{}", goal.done, goal.target);
+    render_paused_text(&text, width, height, fg_color, goal_color, padding)
+}
+
+/// Render the `today` role: work phases completed today, with the daily goal
+/// bar if a goal is set, on the goal color once it is met
+#[allow(clippy::too_many_arguments)]
+pub fn render_today(
+    timer: &Timer,
+    width: u32,
+    height: u32,
+    fg_color: Rgba<u8>,
+    empty_bg: Rgba<u8>,
+    goal_color: Rgba<u8>,
+    padding: f32,
+    label: &str,
+) -> RgbImage {
+    let goal = timer.goal_progress();
+    let (text, bg) = match goal {
+        Some(goal) if goal.done >= goal.target => {
+            (format!("{}/{}", goal.done, goal.target), goal_color)
+        }
+        Some(goal) => (format!("{}/{}", goal.done, goal.target), empty_bg),
+        None => (timer.completed_today().to_string(), empty_bg),
+    };
+
+    let mut rgba = RgbaImage::new(width, height);
+    draw_filled_rect_mut(&mut rgba, Rect::at(0, 0).of_size(width, height), bg);
+    draw_text_hcentered(&mut rgba, label, fg_color, 14.0, 4);
+    draw_centered_text_with_reserved(&mut rgba, &text, fg_color, padding, 18.0, 18.0, 0.0);
+    let rgb = rgba_to_rgb(&rgba);
+
+    match goal {
+        Some(goal) => render_goal_bar(&rgb, goal, goal_color, empty_bg),
+        None => rgb,
+    }
+}
+
+/// Render the `skip` role: the phase a skip moves on to, in that phase's color
+/// while skipping is possible and in the paused color while it is not
+#[allow(clippy::too_many_arguments)]
+pub fn render_skip(
+    timer: &Timer,
+    width: u32,
+    height: u32,
+    fg_color: Rgba<u8>,
+    next_bg: Rgba<u8>,
+    paused_bg: Rgba<u8>,
+    padding: f32,
+    label: &str,
+) -> RgbImage {
+    // A timer paused at the start of a phase has nothing to skip
+    let can_skip = timer.is_running() || !timer.at_phase_boundary();
+    let bg = if can_skip { next_bg } else { paused_bg };
+
+    let mut rgba = RgbaImage::new(width, height);
+    draw_filled_rect_mut(&mut rgba, Rect::at(0, 0).of_size(width, height), bg);
+    draw_text_hcentered(&mut rgba, label, fg_color, 14.0, 4);
+    draw_centered_text_with_reserved(
+        &mut rgba,
+        &timer.next_phase().label,
+        fg_color,
+        padding,
+        18.0,
+        0.0,
+        0.0,
+    );
+
+    rgba_to_rgb(&rgba)
+}
+
+/// Render the `slice` role: this key's share of a progress bar spanning
+/// `slice.count` keys, with the remaining time written across the whole bar
+#[allow(clippy::too_many_arguments)]
+pub fn render_slice(
+    timer: &Timer,
+    width: u32,
+    height: u32,
+    slice: Slice,
+    fg_color: Rgba<u8>,
+    phase_bg: Rgba<u8>,
+    paused_bg: Rgba<u8>,
+    empty_bg: Rgba<u8>,
+    dot_color: Rgba<u8>,
+    fill_direction: FillDirection,
+) -> RgbImage {
+    // Only this key's part of the row is drawn, offset by the keys before it
+    let row_width = width * slice.count;
+    let offset = width * slice.index;
+    let fill_color = if timer.is_running() {
+        phase_bg
+    } else {
+        paused_bg
+    };
+    let filled = (row_width as f32 * timer.progress_ratio()) as u32;
+    let fill = match fill_direction {
+        // Fill from the left
+        FillDirection::EmptyToFull => 0..filled,
+        // Drain towards the right
+        FillDirection::FullToEmpty => filled..row_width,
+    };
+    let part = |x: u32, part_width: u32| {
+        slice_row_part(x..x + part_width, height, &fill, fill_color, empty_bg)
+    };
+    let mut key = part(offset, width);
+
+    // The time and dots are centered on the whole row: draw them on a strip
+    // around its center, just wide enough for them, and copy in this key's share
+    let text = timer.remaining_formatted();
+    // As large as the row allows, up to half the key height
+    let size = (height as f32 * 0.5).min(row_width as f32 / 3.5);
+    let text_width = (size * text.chars().count() as f32).ceil() as u32;
+    let dots_width = iteration_dots_width(timer.works_per_cycle(), row_width);
+    let mut strip_width = text_width.max(dots_width).clamp(1, row_width);
+    // Same parity as the row, so the strip's center is the row's
+    strip_width += (row_width - strip_width) % 2;
+    let strip_x = (row_width - strip_width) / 2;
+
+    let mut strip = part(strip_x, strip_width);
+    let y = ((height as f32 - size) / 2.0) as i32;
+    draw_text_hcentered(&mut strip, &text, fg_color, size, y);
+    draw_iteration_dots(
+        &mut strip,
+        display_iterations(timer),
+        timer.works_per_cycle(),
+        row_width,
+        dot_color,
+    );
+    for x in strip_x.max(offset)..(strip_x + strip_width).min(offset + width) {
+        for y in 0..height {
+            key.put_pixel(x - offset, y, *strip.get_pixel(x - strip_x, y));
+        }
+    }
+    rgba_to_rgb(&key)
+}
+
+/// Draw the columns `columns` of a slice row: empty, filled over `fill`
+fn slice_row_part(
+    columns: Range<u32>,
+    height: u32,
+    fill: &Range<u32>,
+    fill_color: Rgba<u8>,
+    empty_bg: Rgba<u8>,
+) -> RgbaImage {
+    let width = columns.end - columns.start;
+    let mut part = RgbaImage::new(width, height);
+    draw_filled_rect_mut(&mut part, Rect::at(0, 0).of_size(width, height), empty_bg);
+
+    let start = fill.start.max(columns.start);
+    let end = fill.end.min(columns.end);
+    if end > start {
+        draw_filled_rect_mut(
+            &mut part,
+            Rect::at((start - columns.start) as i32, 0).of_size(end - start, height),
+            fill_color,
+        );
+    }
+    part
+}
+
 /// Render an icon image, scaling to fit the button
 fn render_icon(icon: &PluginImage, width: u32, height: u32) -> RgbImage {
     let src_img = bytes_to_rgb(icon.width, icon.height, &icon.data);
@@ -518,12 +1318,13 @@ fn render_icon_with_dots(
     width: u32,
     height: u32,
     display_iters: u8,
+    total_iters: u8,
     dot_color: Rgba<u8>,
 ) -> RgbImage {
     let rgb = render_icon(icon, width, height);
     let mut rgba = rgb_to_rgba(&rgb);
 
-    draw_iteration_dots(&mut rgba, display_iters, width, dot_color);
+    draw_iteration_dots(&mut rgba, display_iters, total_iters, width, dot_color);
 
     rgba_to_rgb(&rgba)
 }
@@ -534,8 +1335,8 @@ fn render_icon_with_dots(
 /// During break: iterations already reflects completed work
 fn display_iterations(timer: &Timer) -> u8 {
     let work_has_started = timer.is_running() || !timer.at_phase_boundary();
-    if timer.phase() == Phase::Work && work_has_started {
-        (timer.iterations() + 1).min(4)
+    if !timer.phase().is_break() && work_has_started {
+        (timer.iterations() + 1).min(timer.works_per_cycle())
     } else {
         timer.iterations()
     }
@@ -558,20 +1359,51 @@ fn draw_bottom_indicator(
             draw_text_hcentered(rgba, phase, fg_color, 14.0, y);
         }
     } else {
-        draw_iteration_dots(rgba, display_iterations(timer), width, dot_color);
+        draw_iteration_dots(
+            rgba,
+            display_iterations(timer),
+            timer.works_per_cycle(),
+            width,
+            dot_color,
+        );
+    }
+}
+
+/// Horizontal advance of one dot glyph at the 18px dot scale
+const DOT_ADVANCE_PX: u32 = 14;
+
+/// Widest [`draw_iteration_dots`] draws for `total_iterations` on an image `width` wide
+fn iteration_dots_width(total_iterations: u8, width: u32) -> u32 {
+    let dots = u32::from(total_iterations) * DOT_ADVANCE_PX;
+    if dots > width {
+        // "●n/m" at 16px, at most eight characters
+        8 * 16
+    } else {
+        dots
     }
 }
 
 /// Draw iteration progress dots at the bottom
 /// `display_iterations` should account for the current phase (work shows +1)
+///
+/// One dot per work iteration in the cycle; when there are too many to fit
+/// across the key, falls back to a compact `●N/M` counter.
 fn draw_iteration_dots(
     rgba: &mut RgbaImage,
     display_iterations: u8,
-    _width: u32,
+    total_iterations: u8,
+    width: u32,
     dot_color: Rgba<u8>,
 ) {
+    if u32::from(total_iterations) * DOT_ADVANCE_PX > width {
+        let counter = format!("●{display_iterations}/{total_iterations}");
+        let y = rgba.height() as i32 - 20; // 16px scale + ~4px margin from bottom
+        draw_text_hcentered(rgba, &counter, dot_color, 16.0, y);
+        return;
+    }
+
     // Build dots string: filled for active/completed, empty for remaining
-    let dots: String = (0..4)
+    let dots: String = (0..total_iterations)
         .map(|i| if i < display_iterations { '●' } else { '○' })
         .collect();
 
@@ -587,7 +1419,6 @@ fn render_ripen_mode(
     height: u32,
     fg_color: Rgba<u8>,
     phase_icon: Option<&PluginImage>,
-    phases: &HashMap<String, String>,
     paused_text: &str,
     phase_indicator_display: PhaseIndicatorDisplay,
     pulse_on_pause: bool,
@@ -606,7 +1437,7 @@ fn render_ripen_mode(
         // Just show a green-ish background
         let green_bg = Rgba([60, 120, 60, 255]);
         draw_filled_rect_mut(&mut rgba, Rect::at(0, 0).of_size(width, height), green_bg);
-        let phase_indicator = get_phase_indicator(timer, phases);
+        let phase_indicator = get_phase_indicator(timer);
         render_overlay(
             &mut rgba,
             timer,
@@ -617,6 +1448,7 @@ fn render_ripen_mode(
                 phase_indicator: Some(phase_indicator),
                 phase_indicator_display,
                 paused_text: None,
+                show_count_up: true,
             },
         );
         return rgba_to_rgb(&rgba);
@@ -647,7 +1479,7 @@ fn render_ripen_mode(
     }
 
     // Render common overlay elements
-    let phase_indicator = get_phase_indicator(timer, phases);
+    let phase_indicator = get_phase_indicator(timer);
     render_overlay(
         &mut rgba,
         timer,
@@ -658,6 +1490,7 @@ fn render_ripen_mode(
             phase_indicator: Some(phase_indicator),
             phase_indicator_display,
             paused_text: Some(paused_text),
+            show_count_up: true,
         },
     );
 
@@ -735,3 +1568,82 @@ fn shift_hue_towards_green(r: u8, g: u8, b: u8, factor: f32) -> (u8, u8, u8) {
         ((b1 + m) * 255.0 + 0.5) as u8,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::config::{Config, ConfigBuilder};
+    use crate::timer::Stamp;
+
+    const FG: Rgba<u8> = Rgba([255, 255, 255, 255]);
+    const PHASE_BG: Rgba<u8> = Rgba([200, 0, 0, 255]);
+    const PAUSED_BG: Rgba<u8> = Rgba([100, 100, 100, 255]);
+    const EMPTY_BG: Rgba<u8> = Rgba([0, 0, 200, 255]);
+
+    /// Top row of every key in a row of `count` slices, side by side
+    fn slice_tops(timer: &Timer, count: u32, fill_direction: FillDirection) -> Vec<[u8; 3]> {
+        (0..count)
+            .flat_map(|index| {
+                let key = render_slice(
+                    timer,
+                    72,
+                    72,
+                    Slice { index, count },
+                    FG,
+                    PHASE_BG,
+                    PAUSED_BG,
+                    EMPTY_BG,
+                    FG,
+                    fill_direction,
+                );
+                (0..key.width())
+                    .map(|x| key.get_pixel(x, 0).0)
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_slices_tile_the_row() -> crate::error::Result<()> {
+        let config: Config =
+            verandah_plugin::api::toml::from_str::<ConfigBuilder>("work = 1")?.build();
+        let mut timer = Timer::new(&config);
+        let now = Stamp::now();
+        timer.start_at(now);
+        timer.update_at(now + Duration::from_secs(30));
+
+        // Halfway through, the fill ends in the middle of the second of three keys
+        let filled = [PHASE_BG[0], PHASE_BG[1], PHASE_BG[2]];
+        let empty = [EMPTY_BG[0], EMPTY_BG[1], EMPTY_BG[2]];
+        let tops = slice_tops(&timer, 3, FillDirection::EmptyToFull);
+        assert_eq!(tops.len(), 3 * 72);
+        assert!(tops[..72 + 36].iter().all(|pixel| *pixel == filled));
+        assert!(tops[72 + 36..].iter().all(|pixel| *pixel == empty));
+
+        let tops = slice_tops(&timer, 3, FillDirection::FullToEmpty);
+        assert!(tops[..72 + 36].iter().all(|pixel| *pixel == empty));
+        assert!(tops[72 + 36..].iter().all(|pixel| *pixel == filled));
+        Ok(())
+    }
+
+    #[test]
+    fn test_slice_row_parts_join_up() -> crate::error::Result<()> {
+        let fill = 50..130;
+        let whole = slice_row_part(0..200, 4, &fill, PHASE_BG, EMPTY_BG);
+        // Parts of any width drawn side by side match the row drawn at once
+        for (start, end) in [(0, 50), (50, 51), (51, 129), (129, 130), (130, 200)] {
+            let part = slice_row_part(start..end, 4, &fill, PHASE_BG, EMPTY_BG);
+            assert_eq!(part.width(), end - start);
+            for x in 0..part.width() {
+                assert_eq!(part.get_pixel(x, 3), whole.get_pixel(start + x, 3));
+            }
+        }
+        assert_eq!(*whole.get_pixel(49, 0), EMPTY_BG);
+        assert_eq!(*whole.get_pixel(50, 0), PHASE_BG);
+        assert_eq!(*whole.get_pixel(129, 0), PHASE_BG);
+        assert_eq!(*whole.get_pixel(130, 0), EMPTY_BG);
+        Ok(())
+    }
+}
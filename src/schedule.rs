@@ -0,0 +1,132 @@
+//! Phase schedules: the ordered list of phases the timer cycles through
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use strum::AsRefStr;
+
+/// Whether a phase is focused work or a break
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, AsRefStr)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum PhaseKind {
+    #[default]
+    Work,
+    Break,
+}
+
+impl PhaseKind {
+    pub fn is_break(self) -> bool {
+        self == PhaseKind::Break
+    }
+}
+
+/// A single phase of a schedule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhaseSpec {
+    /// Unique name, also used to address the phase from the socket
+    pub name: String,
+    /// Work or break; drives iteration counting and default colors
+    pub kind: PhaseKind,
+    /// Phase length in seconds
+    pub duration_secs: u64,
+    /// Start running automatically when the previous phase completes
+    pub auto_start: bool,
+    /// Key into the widget's icons
+    pub icon: String,
+    /// Sound played when this phase starts
+    pub sound: Option<String>,
+    /// Short indicator text shown at the top/bottom of the key
+    pub indicator: String,
+    /// Text shown when paused at the start of the phase and no icon is available
+    pub label: String,
+    /// Summary template for the notification posted when this phase comes up
+    pub notify_summary: String,
+    /// Body template for the notification posted when this phase comes up
+    pub notify_body: String,
+    /// Color overrides while this phase is active (`bg` plus any global color key)
+    pub colors: HashMap<String, String>,
+}
+
+impl PhaseSpec {
+    pub fn is_break(&self) -> bool {
+        self.kind.is_break()
+    }
+}
+
+/// The ordered cycle of phases; completing the last phase completes a session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    phases: Vec<PhaseSpec>,
+}
+
+impl Schedule {
+    /// Create a schedule that starts with `first`, so it is never empty
+    pub fn new(first: PhaseSpec, rest: Vec<PhaseSpec>) -> Self {
+        let mut phases = Vec::with_capacity(rest.len() + 1);
+        phases.push(first);
+        phases.extend(rest);
+        Schedule { phases }
+    }
+
+    pub fn phases(&self) -> &[PhaseSpec] {
+        &self.phases
+    }
+
+    /// Get the phase at `index`, wrapping around the end of the cycle
+    pub fn get(&self, index: usize) -> &PhaseSpec {
+        &self.phases[index % self.phases.len()]
+    }
+
+    /// Index of the first phase called `name`
+    pub fn position<S>(&self, name: S) -> Option<usize>
+    where
+        S: AsRef<str>,
+    {
+        self.phases.iter().position(|p| p.name == name.as_ref())
+    }
+
+    /// Number of work phases in one cycle
+    pub fn work_count(&self) -> u8 {
+        let count = self.phases.iter().filter(|p| !p.is_break()).count();
+        count.min(u8::MAX as usize) as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phase(name: &str, kind: PhaseKind) -> PhaseSpec {
+        PhaseSpec {
+            name: name.to_string(),
+            kind,
+            duration_secs: 60,
+            auto_start: false,
+            icon: name.to_string(),
+            sound: None,
+            indicator: name.to_string(),
+            label: name.to_string(),
+            notify_summary: String::new(),
+            notify_body: String::new(),
+            colors: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_schedule_lookup() {
+        let schedule = Schedule::new(
+            phase("warm-up", PhaseKind::Work),
+            vec![
+                phase("work", PhaseKind::Work),
+                phase("break", PhaseKind::Break),
+                phase("review", PhaseKind::Work),
+            ],
+        );
+        assert_eq!(schedule.phases().len(), 4);
+        assert_eq!(schedule.work_count(), 3);
+        assert_eq!(schedule.position("break"), Some(2));
+        assert_eq!(schedule.position("lunch"), None);
+        assert_eq!(schedule.get(5).name, "work");
+    }
+}
@@ -1,22 +1,40 @@
 use std::{
-    fs,
-    io::Read,
+    fmt, fs,
+    io::{BufRead, BufReader, Read, Write},
+    net::Shutdown,
     os::unix::net::{UnixListener, UnixStream},
     path::{Path, PathBuf},
     sync::{
-        Arc,
+        Arc, Mutex,
         atomic::{AtomicBool, Ordering},
-        mpsc::{self, Receiver, Sender},
+        mpsc::{self, Receiver, RecvTimeoutError, Sender, SyncSender, TrySendError},
     },
     thread::{self, JoinHandle},
+    time::Duration,
 };
 
+use serde::{Deserialize, Serialize};
 use xdg::BaseDirectories;
 
-use crate::timer::Timer;
+use crate::{
+    protocol::{self, ErrorCode},
+    timer::{Timer, Transition, format_secs},
+};
 
 const SOCKET_DIR: &str = "verandah-plugin-pomodoro";
-const SOCKET_NAME: &str = "pomodoro.socket";
+const SOCKET_STEM: &str = "pomodoro";
+const SOCKET_EXTENSION: &str = "socket";
+
+/// How long a client connection may take to send its command
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long a write may block before the client is given up on
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long a JSON connection may sit idle between requests
+const IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+/// Event lines buffered per subscriber before it starts missing events
+const SUBSCRIBER_BUFFER: usize = 64;
+/// How long to wait for the plugin to pick up a command (it polls at the widget interval)
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Commands that can be sent to the timer
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -26,86 +44,496 @@ pub enum Command {
     Stop,
     Reset,
     Skip,
+    Status,
+    /// `extend:<duration>`: lengthen the current phase
+    Extend(Duration),
+    /// `shorten:<duration>`: shorten the current phase
+    Shorten(Duration),
+    /// `set:<duration>`: set the time remaining in the current phase
+    Set(Duration),
+    /// `phase:<name>`: jump to the start of a phase
+    Phase(String),
+}
+
+/// Why a command could not be parsed or applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl CommandError {
+    fn invalid_argument<S>(message: S) -> Self
+    where
+        S: Into<String>,
+    {
+        CommandError {
+            code: ErrorCode::InvalidArgument,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
 }
 
 impl Command {
-    pub fn parse<S>(s: S) -> Option<Self>
+    /// Parse a verb, optionally followed by `:<argument>`
+    pub fn parse<S>(s: S) -> Result<Self, CommandError>
     where
         S: AsRef<str>,
     {
-        match s.as_ref().trim().to_lowercase().as_str() {
-            "toggle" => Some(Command::Toggle),
-            "start" => Some(Command::Start),
-            "stop" => Some(Command::Stop),
-            "reset" => Some(Command::Reset),
-            "skip" => Some(Command::Skip),
-            _ => None,
+        let s = s.as_ref().trim();
+        let (verb, arg) = match s.split_once(':') {
+            Some((verb, arg)) => (verb, Some(arg.trim())),
+            None => (s, None),
+        };
+        let duration = || {
+            let arg = arg.ok_or_else(|| {
+                CommandError::invalid_argument(format!("{verb} needs a duration, e.g. {verb}:5m"))
+            })?;
+            parse_duration(arg)
+                .ok_or_else(|| CommandError::invalid_argument(format!("invalid duration: {arg}")))
+        };
+
+        let command = match verb.to_lowercase().as_str() {
+            "extend" => Command::Extend(duration()?),
+            "shorten" => Command::Shorten(duration()?),
+            "set" => Command::Set(duration()?),
+            "phase" => match arg {
+                Some(name) if !name.is_empty() => Command::Phase(name.to_string()),
+                _ => {
+                    return Err(CommandError::invalid_argument(
+                        "phase needs a phase name, e.g. phase:long_break",
+                    ));
+                }
+            },
+            _ if arg.is_some() => {
+                return Err(CommandError::invalid_argument(format!(
+                    "{verb} does not take an argument"
+                )));
+            }
+            "toggle" => Command::Toggle,
+            "start" => Command::Start,
+            "stop" => Command::Stop,
+            "reset" => Command::Reset,
+            "skip" => Command::Skip,
+            "status" => Command::Status,
+            _ => {
+                return Err(CommandError {
+                    code: ErrorCode::UnknownCommand,
+                    message: format!("unknown command: {s}"),
+                });
+            }
+        };
+        Ok(command)
+    }
+
+    /// Apply the command to the timer
+    /// Returns false if the command had no effect (e.g. `start` while already running)
+    pub fn apply(&self, timer: &mut Timer) -> Result<bool, CommandError> {
+        let changed = match self {
+            Command::Toggle => {
+                timer.toggle();
+                true
+            }
+            Command::Start => {
+                let was_running = timer.is_running();
+                timer.start();
+                !was_running
+            }
+            Command::Stop => {
+                let was_running = timer.is_running();
+                timer.pause();
+                was_running
+            }
+            Command::Reset => {
+                timer.reset();
+                true
+            }
+            Command::Skip => timer.skip() != Transition::None,
+            Command::Status => false,
+            Command::Extend(by) => timer.extend(*by),
+            Command::Shorten(by) => timer.shorten(*by),
+            Command::Set(remaining) => timer.set_remaining(*remaining),
+            Command::Phase(name) => {
+                if !timer.jump_to(name) {
+                    return Err(CommandError::invalid_argument(format!(
+                        "no phase called {name} in the schedule"
+                    )));
+                }
+                true
+            }
+        };
+        Ok(changed)
+    }
+}
+
+/// Parse a duration such as `5m`, `90s`, `1h30m`; a bare number is minutes
+fn parse_duration(s: &str) -> Option<Duration> {
+    if let Ok(mins) = s.parse::<u64>() {
+        return Some(Duration::from_secs(mins.saturating_mul(60)));
+    }
+    if s.is_empty() {
+        return None;
+    }
+
+    let mut total: u64 = 0;
+    let mut digits = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let value: u64 = digits.parse().ok()?;
+        digits.clear();
+        let unit = match c {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+        total = total.saturating_add(value.saturating_mul(unit));
+    }
+
+    // Trailing digits without a unit are ambiguous
+    digits.is_empty().then_some(Duration::from_secs(total))
+}
+
+/// A command received on the socket, with a channel for the reply
+#[derive(Debug)]
+pub struct Request {
+    pub command: Command,
+    pub reply: Sender<Reply>,
+}
+
+/// Snapshot of the timer reported back to clients
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Status {
+    pub phase: String,
+    pub kind: String,
+    pub remaining_secs: u64,
+    /// Full length of the current phase
+    #[serde(default)]
+    pub duration_secs: u64,
+    pub running: bool,
+    pub iterations: u8,
+    pub sessions_completed: u32,
+    /// Time run past the end of a work phase with `overtime` enabled
+    #[serde(default)]
+    pub overtime_secs: u64,
+    /// Time run in the current phase
+    #[serde(default)]
+    pub elapsed_secs: u64,
+}
+
+impl From<&Timer> for Status {
+    fn from(timer: &Timer) -> Self {
+        let phase = timer.phase();
+        Status {
+            phase: phase.name.clone(),
+            kind: phase.kind.as_ref().to_string(),
+            remaining_secs: timer.remaining_secs(),
+            duration_secs: timer.current_duration(),
+            running: timer.is_running(),
+            iterations: timer.iterations(),
+            sessions_completed: timer.sessions_completed(),
+            overtime_secs: timer.overtime_secs(),
+            elapsed_secs: timer.elapsed_secs(),
+        }
+    }
+}
+
+impl Status {
+    /// Remaining time as `MM:SS`, `+MM:SS` in overtime, or the time counted so far
+    pub fn remaining_formatted(&self) -> String {
+        // A flowtime work phase has no length and counts up
+        if self.duration_secs == 0 {
+            format_secs(self.elapsed_secs)
+        } else if self.overtime_secs > 0 {
+            format!("+{}", format_secs(self.overtime_secs))
+        } else {
+            format_secs(self.remaining_secs)
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "phase={}", self.phase)?;
+        writeln!(f, "kind={}", self.kind)?;
+        writeln!(f, "remaining={}", self.remaining_secs)?;
+        writeln!(f, "duration={}", self.duration_secs)?;
+        writeln!(f, "running={}", self.running)?;
+        writeln!(f, "iterations={}", self.iterations)?;
+        writeln!(f, "sessions={}", self.sessions_completed)?;
+        writeln!(f, "overtime={}", self.overtime_secs)?;
+        writeln!(f, "elapsed={}", self.elapsed_secs)
+    }
+}
+
+/// Reply sent back for every command
+///
+/// The first line is `ok`, `unchanged` or `error: <message>`; successful
+/// replies are followed by the timer status as `key=value` lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reply {
+    /// The command was applied
+    Ok(Status),
+    /// The command was valid but had no effect
+    Unchanged(Status),
+    /// The command could not be applied
+    Error(ErrorCode, String),
+}
+
+impl From<CommandError> for Reply {
+    fn from(error: CommandError) -> Self {
+        Reply::Error(error.code, error.message)
+    }
+}
+
+impl Reply {
+    /// Build the reply for a command that was just applied
+    pub fn applied(changed: bool, timer: &Timer) -> Self {
+        if changed {
+            Reply::Ok(Status::from(timer))
+        } else {
+            Reply::Unchanged(Status::from(timer))
         }
     }
+}
 
-    pub fn apply(&self, timer: &mut Timer) {
+impl fmt::Display for Reply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Command::Toggle => timer.toggle(),
-            Command::Start => timer.start(),
-            Command::Stop => timer.pause(),
-            Command::Reset => timer.reset(),
-            Command::Skip => {
-                let _ = timer.skip();
-            }
+            Reply::Ok(status) => write!(f, "ok\n{status}"),
+            Reply::Unchanged(status) => write!(f, "unchanged\n{status}"),
+            Reply::Error(_, message) => writeln!(f, "error: {message}"),
         }
     }
 }
 
-/// Get the socket path using XDG runtime directory
-pub fn get_socket_path() -> Option<PathBuf> {
+/// A widget instance with a socket in the runtime directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instance {
+    /// The instance id, None for a widget started without one
+    pub id: Option<String>,
+    pub socket_path: PathBuf,
+}
+
+/// Make a widget id safe to use in file names
+///
+/// Anything but ASCII letters, digits, `-`, `_` and `.` becomes `_`.
+pub fn sanitize_instance_id<S>(id: S) -> String
+where
+    S: AsRef<str>,
+{
+    id.as_ref()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// File name of the socket for `instance` (`pomodoro-<id>.socket`, or `pomodoro.socket`)
+fn socket_file_name(instance: Option<&str>) -> String {
+    match instance {
+        Some(id) => format!(
+            "{SOCKET_STEM}-{}.{SOCKET_EXTENSION}",
+            sanitize_instance_id(id)
+        ),
+        None => format!("{SOCKET_STEM}.{SOCKET_EXTENSION}"),
+    }
+}
+
+/// The instance a socket file name belongs to, or None if it is not a pomodoro socket
+fn instance_from_file_name(name: &str) -> Option<Option<String>> {
+    let stem = name.strip_suffix(SOCKET_EXTENSION)?.strip_suffix('.')?;
+    if stem == SOCKET_STEM {
+        return Some(None);
+    }
+    let id = stem.strip_prefix(SOCKET_STEM)?.strip_prefix('-')?;
+    (!id.is_empty()).then(|| Some(id.to_string()))
+}
+
+/// Get the socket path for `instance` using XDG runtime directory
+pub fn get_socket_path(instance: Option<&str>) -> Option<PathBuf> {
     let xdg = BaseDirectories::with_prefix(SOCKET_DIR);
-    xdg.place_runtime_file(SOCKET_NAME).ok()
+    xdg.place_runtime_file(socket_file_name(instance)).ok()
 }
 
-/// Find existing socket for the control client
-pub fn find_socket() -> Option<PathBuf> {
+/// Every instance with a socket in the runtime directory, sorted by id
+pub fn list_instances() -> Vec<Instance> {
     let xdg = BaseDirectories::with_prefix(SOCKET_DIR);
 
-    xdg.list_runtime_files(".")
+    let mut instances: Vec<Instance> = xdg
+        .list_runtime_files(".")
         .into_iter()
-        .find(|path: &PathBuf| path.file_name().map(|n| n == SOCKET_NAME).unwrap_or(false))
+        .filter_map(|path| {
+            let id = instance_from_file_name(path.file_name()?.to_str()?)?;
+            Some(Instance {
+                id,
+                socket_path: path,
+            })
+        })
+        .collect();
+    instances.sort_by(|a, b| a.id.cmp(&b.id));
+    instances
 }
 
-/// Send a command to a running pomodoro instance
-pub fn send_command<S>(command: S) -> std::io::Result<()>
+/// Find the socket of a running instance for the control client
+///
+/// Without an `instance` id the only running instance is picked; it is an
+/// error to leave the id out while several are running.
+pub fn find_socket(instance: Option<&str>) -> std::io::Result<PathBuf> {
+    if let Some(id) = instance {
+        return get_socket_path(Some(id))
+            .filter(|path| path.exists())
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("No pomodoro instance {id} found"),
+                )
+            });
+    }
+
+    let mut instances = list_instances();
+    match instances.len() {
+        0 => Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "No running pomodoro instance found",
+        )),
+        1 => Ok(instances.remove(0).socket_path),
+        n => Err(std::io::Error::other(format!(
+            "{n} pomodoro instances are running, pick one with --instance \
+             (see verandah-pomodoroctl list)"
+        ))),
+    }
+}
+
+/// Send a command to a running pomodoro instance and return its reply
+pub fn send_command<S>(instance: Option<&str>, command: S) -> std::io::Result<String>
 where
     S: AsRef<str>,
 {
-    let socket_path = find_socket().ok_or_else(|| {
-        std::io::Error::new(
-            std::io::ErrorKind::NotFound,
-            "No running pomodoro instance found",
-        )
-    })?;
+    send_command_to(find_socket(instance)?, command)
+}
+
+/// Send a command to the instance listening on `socket_path` and return its reply
+pub fn send_command_to<P, S>(socket_path: P, command: S) -> std::io::Result<String>
+where
+    P: AsRef<Path>,
+    S: AsRef<str>,
+{
+    let mut stream = UnixStream::connect(socket_path)?;
+    stream.write_all(command.as_ref().as_bytes())?;
+    // Signal the end of the command so the listener can reply
+    stream.shutdown(Shutdown::Write)?;
+
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply)?;
+    Ok(reply)
+}
 
-    let mut stream = UnixStream::connect(&socket_path)?;
-    std::io::Write::write_all(&mut stream, command.as_ref().as_bytes())?;
-    Ok(())
+/// Ask the instance listening on `socket_path` for its status
+pub fn query_status<P>(socket_path: P) -> std::io::Result<Status>
+where
+    P: AsRef<Path>,
+{
+    let stream = UnixStream::connect(socket_path)?;
+    stream.set_read_timeout(Some(REPLY_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    writer.write_all(b"{\"cmd\":\"status\"}\n")?;
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    let response: protocol::ServerResponse =
+        serde_json::from_str(&line).map_err(std::io::Error::other)?;
+    match (response.status, response.error) {
+        (Some(status), _) => Ok(status),
+        (None, Some(error)) => Err(std::io::Error::other(error.message)),
+        (None, None) => Err(std::io::Error::other("reply carried no status")),
+    }
+}
+
+/// Fans event lines out to every subscribed connection
+///
+/// Publishing never blocks: a subscriber whose buffer is full misses the
+/// line, and subscribers that have disconnected are dropped.
+#[derive(Debug, Clone, Default)]
+pub struct Broadcaster {
+    subscribers: Arc<Mutex<Vec<SyncSender<String>>>>,
+}
+
+impl Broadcaster {
+    /// Register a new subscriber and return its end of the stream
+    pub fn subscribe(&self) -> Receiver<String> {
+        let (tx, rx) = mpsc::sync_channel(SUBSCRIBER_BUFFER);
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push(tx);
+        }
+        rx
+    }
+
+    pub fn has_subscribers(&self) -> bool {
+        self.subscribers.lock().is_ok_and(|s| !s.is_empty())
+    }
+
+    /// Send `line` to every subscriber
+    pub fn publish(&self, line: &str) {
+        let Ok(mut subscribers) = self.subscribers.lock() else {
+            return;
+        };
+        subscribers.retain(|tx| match tx.try_send(line.to_string()) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                tracing::debug!("Subscriber is not keeping up, dropping event");
+                true
+            }
+            Err(TrySendError::Disconnected(_)) => false,
+        });
+    }
 }
 
 /// Socket listener that receives commands and sends them through a channel
 pub struct SocketListener {
     socket_path: PathBuf,
     shutdown: Arc<AtomicBool>,
+    broadcaster: Broadcaster,
     handle: Option<JoinHandle<()>>,
 }
 
 impl SocketListener {
-    pub fn new(command_tx: Sender<Command>) -> std::io::Result<Self> {
-        let socket_path = get_socket_path()
+    /// Listen on the runtime directory socket for `instance`
+    pub fn new(instance: Option<&str>, request_tx: Sender<Request>) -> std::io::Result<Self> {
+        let socket_path = get_socket_path(instance)
             .ok_or_else(|| std::io::Error::other("Failed to determine XDG runtime directory"))?;
 
+        Self::bind(socket_path, request_tx)
+    }
+
+    /// Listen on `socket_path`, replacing a stale socket left by a crashed process
+    pub fn bind<P>(socket_path: P, request_tx: Sender<Request>) -> std::io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let socket_path = socket_path.as_ref().to_path_buf();
+
         // Check if an existing socket is in use by another instance
         if socket_path.exists() {
             if UnixStream::connect(&socket_path).is_ok() {
-                return Err(std::io::Error::other(
-                    "Another pomodoro instance is already running",
-                ));
+                return Err(std::io::Error::other(format!(
+                    "Another pomodoro instance is already listening on {}",
+                    socket_path.display()
+                )));
             }
             // Stale socket from a crashed process, safe to remove
             fs::remove_file(&socket_path)?;
@@ -116,10 +544,18 @@ impl SocketListener {
 
         let shutdown = Arc::new(AtomicBool::new(false));
         let shutdown_clone = Arc::clone(&shutdown);
+        let broadcaster = Broadcaster::default();
+        let broadcaster_clone = broadcaster.clone();
         let socket_path_clone = socket_path.clone();
 
         let handle = thread::spawn(move || {
-            Self::listen_loop(listener, command_tx, shutdown_clone, &socket_path_clone);
+            Self::listen_loop(
+                listener,
+                request_tx,
+                shutdown_clone,
+                broadcaster_clone,
+                &socket_path_clone,
+            );
         });
 
         tracing::info!(path = %socket_path.display(), "Socket listener started");
@@ -127,35 +563,41 @@ impl SocketListener {
         Ok(SocketListener {
             socket_path,
             shutdown,
+            broadcaster,
             handle: Some(handle),
         })
     }
 
+    /// Event stream shared with subscribed connections
+    pub fn broadcaster(&self) -> &Broadcaster {
+        &self.broadcaster
+    }
+
     fn listen_loop(
         listener: UnixListener,
-        tx: Sender<Command>,
+        tx: Sender<Request>,
         shutdown: Arc<AtomicBool>,
+        broadcaster: Broadcaster,
         socket_path: &Path,
     ) {
+        // Connection threads still running, each with its end of the connection
+        let mut connections: Vec<(JoinHandle<()>, UnixStream)> = Vec::new();
         while !shutdown.load(Ordering::Relaxed) {
+            connections.retain(|(handle, _)| !handle.is_finished());
             match listener.accept() {
-                Ok((mut stream, _)) => {
-                    let mut message = String::new();
-                    if let Err(e) = stream.read_to_string(&mut message) {
-                        tracing::warn!(error = %e, "Failed to read from socket");
+                Ok((stream, _)) => {
+                    let Ok(kept) = stream.try_clone() else {
+                        tracing::warn!("Failed to clone socket connection");
                         continue;
-                    }
-
-                    tracing::debug!(message = %message.trim(), "Received command");
-
-                    if let Some(cmd) = Command::parse(&message) {
-                        if tx.send(cmd).is_err() {
-                            tracing::warn!("Command channel closed");
-                            break;
-                        }
-                    } else {
-                        tracing::warn!(message = %message.trim(), "Unknown command");
-                    }
+                    };
+                    // JSON clients may keep their connection open, so each one gets its own thread
+                    let tx = tx.clone();
+                    let shutdown = Arc::clone(&shutdown);
+                    let broadcaster = broadcaster.clone();
+                    let handle = thread::spawn(move || {
+                        Self::handle_connection(stream, &tx, &shutdown, &broadcaster)
+                    });
+                    connections.push((handle, kept));
                 }
                 Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
                     thread::sleep(std::time::Duration::from_millis(100));
@@ -167,6 +609,12 @@ impl SocketListener {
             }
         }
 
+        // Closing the connections wakes threads blocked reading from them
+        for (handle, stream) in connections {
+            let _ = stream.shutdown(Shutdown::Both);
+            let _ = handle.join();
+        }
+
         // Cleanup socket on exit
         if socket_path.exists() {
             let _ = fs::remove_file(socket_path);
@@ -174,6 +622,160 @@ impl SocketListener {
         tracing::info!("Socket listener stopped");
     }
 
+    /// Serve one client connection
+    ///
+    /// A first line starting with `{` selects the JSON protocol, which handles
+    /// one request per line until the client disconnects. Anything else is a
+    /// plain word command terminated by the client closing its write side.
+    fn handle_connection(
+        stream: UnixStream,
+        tx: &Sender<Request>,
+        shutdown: &AtomicBool,
+        broadcaster: &Broadcaster,
+    ) {
+        // The accepted stream inherits non-blocking mode from the listener
+        if let Err(e) = stream
+            .set_nonblocking(false)
+            .and_then(|()| stream.set_read_timeout(Some(READ_TIMEOUT)))
+            .and_then(|()| stream.set_write_timeout(Some(WRITE_TIMEOUT)))
+        {
+            tracing::warn!(error = %e, "Failed to configure socket connection");
+            return;
+        }
+
+        let mut writer = match stream.try_clone() {
+            Ok(writer) => writer,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to clone socket connection");
+                return;
+            }
+        };
+        let mut reader = BufReader::new(stream);
+
+        let mut line = String::new();
+        if let Err(e) = reader.read_line(&mut line) {
+            tracing::warn!(error = %e, "Failed to read from socket");
+            return;
+        }
+
+        if line.trim_start().starts_with('{') {
+            if let Err(e) = reader.get_ref().set_read_timeout(Some(IDLE_TIMEOUT)) {
+                tracing::warn!(error = %e, "Failed to configure socket connection");
+                return;
+            }
+            Self::serve_json(line, reader, writer, tx, shutdown, broadcaster);
+            return;
+        }
+
+        if let Err(e) = reader.read_to_string(&mut line) {
+            tracing::warn!(error = %e, "Failed to read from socket");
+            return;
+        }
+
+        tracing::debug!(message = %line.trim(), "Received command");
+
+        let reply = match Command::parse(&line) {
+            Ok(command) => Self::dispatch(command, tx),
+            Err(e) => {
+                tracing::warn!(message = %line.trim(), error = %e, "Invalid command");
+                Reply::from(e)
+            }
+        };
+
+        if let Err(e) = writer.write_all(reply.to_string().as_bytes()) {
+            tracing::debug!(error = %e, "Failed to write reply");
+        }
+    }
+
+    /// Answer JSON requests line by line, starting with the already-read `first` line
+    fn serve_json(
+        first: String,
+        mut reader: BufReader<UnixStream>,
+        mut writer: UnixStream,
+        tx: &Sender<Request>,
+        shutdown: &AtomicBool,
+        broadcaster: &Broadcaster,
+    ) {
+        let mut line = first;
+        loop {
+            if !line.trim().is_empty() {
+                tracing::debug!(message = %line.trim(), "Received JSON request");
+                let response = protocol::handle_line(&line, |command| Self::dispatch(command, tx));
+                if let Err(e) = writer.write_all(response.to_line().as_bytes()) {
+                    tracing::debug!(error = %e, "Failed to write response");
+                    return;
+                }
+                if response.is_subscription() {
+                    Self::stream_events(writer, broadcaster, shutdown);
+                    return;
+                }
+            }
+
+            if shutdown.load(Ordering::Relaxed) {
+                return;
+            }
+
+            line.clear();
+            match reader.read_line(&mut line) {
+                // Client closed the connection
+                Ok(0) => return,
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::debug!(error = %e, "JSON connection closed");
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Write every published event line to a subscribed connection until it closes
+    fn stream_events(mut writer: UnixStream, broadcaster: &Broadcaster, shutdown: &AtomicBool) {
+        // A subscriber that stops reading is dropped rather than blocking this thread
+        if let Err(e) = writer.set_write_timeout(Some(WRITE_TIMEOUT)) {
+            tracing::warn!(error = %e, "Failed to configure subscriber connection");
+            return;
+        }
+        let events = broadcaster.subscribe();
+        tracing::debug!("Subscriber connected");
+
+        while !shutdown.load(Ordering::Relaxed) {
+            match events.recv_timeout(Duration::from_secs(1)) {
+                Ok(line) => {
+                    if let Err(e) = writer.write_all(line.as_bytes()) {
+                        tracing::debug!(error = %e, "Failed to write event, dropping subscriber");
+                        break;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        tracing::debug!("Subscriber disconnected");
+    }
+
+    /// Forward a command to the plugin and wait for its reply
+    fn dispatch(command: Command, tx: &Sender<Request>) -> Reply {
+        let (reply_tx, reply_rx) = mpsc::channel();
+        let request = Request {
+            command,
+            reply: reply_tx,
+        };
+        if tx.send(request).is_err() {
+            return Reply::Error(
+                ErrorCode::Unavailable,
+                "plugin is shutting down".to_string(),
+            );
+        }
+
+        reply_rx.recv_timeout(REPLY_TIMEOUT).unwrap_or_else(|_| {
+            Reply::Error(
+                ErrorCode::Timeout,
+                "timed out waiting for the plugin".to_string(),
+            )
+        })
+    }
+
     pub fn shutdown(&mut self) {
         self.shutdown.store(true, Ordering::Relaxed);
         if let Some(handle) = self.handle.take() {
@@ -191,8 +793,8 @@ impl Drop for SocketListener {
     }
 }
 
-/// Create a command channel
-pub fn command_channel() -> (Sender<Command>, Receiver<Command>) {
+/// Create a request channel
+pub fn command_channel() -> (Sender<Request>, Receiver<Request>) {
     mpsc::channel()
 }
 
@@ -202,13 +804,73 @@ mod tests {
 
     #[test]
     fn test_command_parse() -> crate::error::Result<()> {
-        assert_eq!(Command::parse("toggle"), Some(Command::Toggle));
-        assert_eq!(Command::parse("TOGGLE"), Some(Command::Toggle));
-        assert_eq!(Command::parse("  start  "), Some(Command::Start));
-        assert_eq!(Command::parse("stop"), Some(Command::Stop));
-        assert_eq!(Command::parse("reset"), Some(Command::Reset));
-        assert_eq!(Command::parse("skip"), Some(Command::Skip));
-        assert_eq!(Command::parse("unknown"), None);
+        assert_eq!(Command::parse("toggle").ok(), Some(Command::Toggle));
+        assert_eq!(Command::parse("TOGGLE").ok(), Some(Command::Toggle));
+        assert_eq!(Command::parse("  start  ").ok(), Some(Command::Start));
+        assert_eq!(Command::parse("stop").ok(), Some(Command::Stop));
+        assert_eq!(Command::parse("reset").ok(), Some(Command::Reset));
+        assert_eq!(Command::parse("skip").ok(), Some(Command::Skip));
+        assert_eq!(Command::parse("status").ok(), Some(Command::Status));
+        assert_eq!(
+            Command::parse("unknown").map_err(|e| e.code),
+            Err(ErrorCode::UnknownCommand)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_command_parse_arguments() -> crate::error::Result<()> {
+        let minutes = |m| Duration::from_secs(m * 60);
+        assert_eq!(
+            Command::parse("extend:5m").ok(),
+            Some(Command::Extend(minutes(5)))
+        );
+        assert_eq!(
+            Command::parse("Shorten: 2m").ok(),
+            Some(Command::Shorten(minutes(2)))
+        );
+        assert_eq!(
+            Command::parse("set:10").ok(),
+            Some(Command::Set(minutes(10)))
+        );
+        assert_eq!(
+            Command::parse("set:1h30m15s").ok(),
+            Some(Command::Set(Duration::from_secs(5415)))
+        );
+        assert_eq!(
+            Command::parse("phase:Long_Break").ok(),
+            Some(Command::Phase("Long_Break".to_string()))
+        );
+
+        for invalid in [
+            "extend",
+            "extend:",
+            "extend:5x",
+            "set:1h30",
+            "phase:",
+            "start:now",
+        ] {
+            assert_eq!(
+                Command::parse(invalid).map_err(|e| e.code),
+                Err(ErrorCode::InvalidArgument),
+                "{invalid}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_command_apply_phase() -> crate::error::Result<()> {
+        let config = crate::config::ConfigBuilder::default().build();
+        let mut timer = Timer::new(&config);
+
+        assert_eq!(
+            Command::Phase("long_break".to_string()).apply(&mut timer),
+            Ok(true)
+        );
+        assert_eq!(timer.phase().name, "long_break");
+        let error = Command::Phase("lunch".to_string()).apply(&mut timer);
+        assert_eq!(error.map_err(|e| e.code), Err(ErrorCode::InvalidArgument));
         Ok(())
     }
 
@@ -218,9 +880,9 @@ mod tests {
         let mut timer = Timer::new(&config);
 
         assert!(!timer.is_running());
-        Command::Toggle.apply(&mut timer);
+        Command::Toggle.apply(&mut timer).ok();
         assert!(timer.is_running());
-        Command::Toggle.apply(&mut timer);
+        Command::Toggle.apply(&mut timer).ok();
         assert!(!timer.is_running());
         Ok(())
     }
@@ -231,10 +893,158 @@ mod tests {
         let mut timer = Timer::new(&config);
 
         assert!(!timer.is_running());
-        Command::Start.apply(&mut timer);
+        assert_eq!(Command::Start.apply(&mut timer), Ok(true));
         assert!(timer.is_running());
-        Command::Stop.apply(&mut timer);
+        assert_eq!(Command::Start.apply(&mut timer), Ok(false));
+        assert_eq!(Command::Stop.apply(&mut timer), Ok(true));
         assert!(!timer.is_running());
+        assert_eq!(Command::Stop.apply(&mut timer), Ok(false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_reply_format() -> crate::error::Result<()> {
+        let config = crate::config::ConfigBuilder::default().build();
+        let timer = Timer::new(&config);
+
+        let reply = Reply::applied(false, &timer).to_string();
+        let mut lines = reply.lines();
+        assert_eq!(lines.next(), Some("unchanged"));
+        assert_eq!(lines.next(), Some("phase=work"));
+        assert_eq!(lines.next(), Some("kind=work"));
+        assert_eq!(lines.next(), Some("remaining=1500"));
+        assert_eq!(lines.next(), Some("duration=1500"));
+        assert_eq!(lines.next(), Some("running=false"));
+
+        let error = Reply::Error(
+            ErrorCode::UnknownCommand,
+            "unknown command: dance".to_string(),
+        );
+        assert_eq!(error.to_string(), "error: unknown command: dance\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_instance_socket_names() -> crate::error::Result<()> {
+        assert_eq!(socket_file_name(None), "pomodoro.socket");
+        assert_eq!(
+            socket_file_name(Some("deck 1/key:3")),
+            "pomodoro-deck_1_key_3.socket"
+        );
+        assert_eq!(
+            instance_from_file_name("pomodoro-deck_1_key_3.socket"),
+            Some(Some("deck_1_key_3".to_string()))
+        );
+        assert_eq!(instance_from_file_name("pomodoro.socket"), Some(None));
+        for other in [
+            "pomodoro-.socket",
+            "pomodoro.sock",
+            "other.socket",
+            "timer.json",
+        ] {
+            assert_eq!(instance_from_file_name(other), None, "{other}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_broadcaster_never_blocks() -> crate::error::Result<()> {
+        let broadcaster = Broadcaster::default();
+        assert!(!broadcaster.has_subscribers());
+
+        let slow = broadcaster.subscribe();
+        let gone = broadcaster.subscribe();
+        drop(gone);
+
+        for i in 0..SUBSCRIBER_BUFFER + 10 {
+            broadcaster.publish(&format!("{i}\n"));
+        }
+
+        // The disconnected subscriber was dropped, the slow one kept its buffer
+        assert_eq!(
+            broadcaster.subscribers.lock().map(|s| s.len()).ok(),
+            Some(1)
+        );
+        assert_eq!(slow.try_iter().count(), SUBSCRIBER_BUFFER);
+        assert_eq!(slow.try_recv().ok(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_socket_roundtrip() -> crate::error::Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "verandah-pomodoro-test-{}.socket",
+            std::process::id()
+        ));
+        let (tx, rx) = command_channel();
+        let _listener = SocketListener::bind(&path, tx)?;
+
+        // Stand in for the plugin's poll loop
+        let plugin = thread::spawn(move || {
+            let config = crate::config::ConfigBuilder::default().build();
+            let mut timer = Timer::new(&config);
+            for request in rx.iter().take(5) {
+                let reply = match request.command.apply(&mut timer) {
+                    Ok(changed) => Reply::applied(changed, &timer),
+                    Err(e) => Reply::from(e),
+                };
+                let _ = request.reply.send(reply);
+            }
+        });
+
+        assert!(send_command_to(&path, "start")?.starts_with("ok\n"));
+        assert!(send_command_to(&path, "start")?.starts_with("unchanged\n"));
+        let status = send_command_to(&path, "status")?;
+        assert!(status.contains("running=true\n"));
+        assert!(send_command_to(&path, "dance")?.starts_with("error: "));
+
+        // JSON requests share a connection and get one response line each
+        let stream = UnixStream::connect(&path)?;
+        let mut writer = stream.try_clone()?;
+        let mut reader = BufReader::new(stream);
+        let mut request = |line: &str| -> crate::error::Result<protocol::ServerResponse> {
+            writer.write_all(line.as_bytes())?;
+            let mut response = String::new();
+            reader.read_line(&mut response)?;
+            Ok(serde_json::from_str(&response)?)
+        };
+
+        let hello = request("{\"cmd\":\"hello\",\"v\":1}\n")?;
+        assert_eq!(hello.v, protocol::PROTOCOL_VERSION);
+        let stop = request("{\"cmd\":\"stop\",\"id\":1}\n")?;
+        assert_eq!(stop.result.as_deref(), Some("ok"));
+        assert_eq!(stop.status.map(|s| s.running), Some(false));
+        let status = request("{\"cmd\":\"status\",\"id\":2}\n")?;
+        assert_eq!(status.id, Some(serde_json::Value::from(2)));
+        assert_eq!(status.result.as_deref(), Some("unchanged"));
+
+        let _ = plugin.join();
+        Ok(())
+    }
+
+    #[test]
+    fn test_shutdown_closes_idle_connections() -> crate::error::Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "verandah-pomodoro-shutdown-test-{}.socket",
+            std::process::id()
+        ));
+        let (tx, _rx) = command_channel();
+        let mut listener = SocketListener::bind(&path, tx)?;
+
+        // A JSON client that stays connected without sending anything else
+        let stream = UnixStream::connect(&path)?;
+        let mut writer = stream.try_clone()?;
+        let mut reader = BufReader::new(stream);
+        writer.write_all(b"{\"cmd\":\"hello\",\"v\":1}\n")?;
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        assert!(!line.is_empty());
+
+        // Shutting down waits for its thread, which lets the client go
+        listener.shutdown();
+        line.clear();
+        assert_eq!(reader.read_line(&mut line)?, 0);
+        assert!(!path.exists());
         Ok(())
     }
 }
@@ -1,4 +1,4 @@
-//! Audio playback for phase transition sounds
+//! Audio playback for phase transition and warning sounds
 
 use std::fs::File;
 use std::io::BufReader;
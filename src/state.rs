@@ -0,0 +1,142 @@
+//! Persistence of timer state across plugin restarts
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use strum::{AsRefStr, EnumString, VariantNames};
+use xdg::BaseDirectories;
+
+use crate::socket::sanitize_instance_id;
+use crate::timer::Snapshot;
+
+const STATE_DIR: &str = "verandah-plugin-pomodoro";
+const STATE_STEM: &str = "timer";
+
+/// How to account for the time that passed while the plugin was down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, AsRefStr, VariantNames)]
+#[strum(serialize_all = "snake_case")]
+pub enum RestorePolicy {
+    /// A running timer keeps counting through the downtime, as if it never stopped
+    #[default]
+    CatchUp,
+    /// Restore the saved position, paused; the downtime is ignored
+    Pause,
+    /// Ignore the saved state and start fresh
+    Discard,
+}
+
+/// File name of the state for `instance` (`timer-<id>.json`, or `timer.json`)
+fn state_file_name(instance: Option<&str>) -> String {
+    match instance {
+        Some(id) => format!("{STATE_STEM}-{}.json", sanitize_instance_id(id)),
+        None => format!("{STATE_STEM}.json"),
+    }
+}
+
+/// Get the state file path for `instance` using the XDG state directory
+pub fn get_state_path(instance: Option<&str>) -> Option<PathBuf> {
+    let xdg = BaseDirectories::with_prefix(STATE_DIR);
+    xdg.place_state_file(state_file_name(instance)).ok()
+}
+
+/// Write a snapshot, replacing the previous one atomically
+pub fn save<P>(path: P, snapshot: &Snapshot) -> std::io::Result<()>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let json = serde_json::to_string(snapshot).map_err(std::io::Error::other)?;
+
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, json)?;
+    fs::rename(&tmp_path, path)
+}
+
+/// Read a previously saved snapshot
+///
+/// Returns `None` if there is no state file or it cannot be parsed.
+pub fn load<P>(path: P) -> Option<Snapshot>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let json = match fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            tracing::warn!(path = %path.display(), error = %e, "Failed to read timer state");
+            return None;
+        }
+    };
+
+    match serde_json::from_str(&json) {
+        Ok(snapshot) => Some(snapshot),
+        Err(e) => {
+            tracing::warn!(path = %path.display(), error = %e, "Ignoring unreadable timer state");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::Timer;
+
+    fn temp_state_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "verandah-pomodoro-{}-{name}.json",
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() -> crate::error::Result<()> {
+        let config = crate::config::ConfigBuilder::default().build();
+        let mut timer = Timer::new(&config);
+        timer.start();
+        timer.skip();
+
+        let path = temp_state_path("roundtrip");
+        save(&path, &timer.snapshot())?;
+        let loaded = load(&path);
+        fs::remove_file(&path)?;
+
+        let Some(loaded) = loaded else {
+            panic!("snapshot should load back");
+        };
+        assert_eq!(loaded.iterations, 1);
+        assert_eq!(loaded.elapsed_secs, 0);
+        assert!(!loaded.running);
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        assert!(load(temp_state_path("missing")).is_none());
+    }
+
+    #[test]
+    fn test_load_garbage_file() -> crate::error::Result<()> {
+        let path = temp_state_path("garbage");
+        fs::write(&path, "not json")?;
+        let loaded = load(&path);
+        fs::remove_file(&path)?;
+        assert!(loaded.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_state_file_per_instance() {
+        assert_eq!(state_file_name(None), "timer.json");
+        assert_eq!(state_file_name(Some("key/3")), "timer-key_3.json");
+    }
+
+    #[test]
+    fn test_restore_policy_parse() {
+        assert_eq!("catch_up".parse(), Ok(RestorePolicy::CatchUp));
+        assert_eq!("pause".parse(), Ok(RestorePolicy::Pause));
+        assert_eq!("discard".parse(), Ok(RestorePolicy::Discard));
+        assert!("bogus".parse::<RestorePolicy>().is_err());
+    }
+}
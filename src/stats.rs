@@ -0,0 +1,321 @@
+//! Summaries of the session history for `verandah-pomodoroctl stats`
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Write};
+
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+
+use crate::cli::{StatsArgs, StatsPeriod};
+use crate::history::{self, Entry, Outcome};
+
+/// Work phases summed over one day, week or month
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PeriodStats {
+    /// `2026-10-12`, `2026-W41` or `2026-10`
+    pub period: String,
+    pub focus_secs: u64,
+    pub completed: u32,
+    pub skipped: u32,
+}
+
+/// Statistics over the work phases in a date range
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Report {
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+    pub periods: Vec<PeriodStats>,
+    /// Time actually run in work phases including overtime, whatever their outcome
+    pub focus_secs: u64,
+    pub completed: u32,
+    pub skipped: u32,
+    /// Work phases that were reset or interrupted
+    pub abandoned: u32,
+    /// Completed work phases as a share of completed and skipped ones
+    pub completion_ratio: Option<f64>,
+    /// Most consecutive days with at least one completed work phase
+    pub longest_streak_days: u32,
+    /// Mean number of pauses per work phase
+    pub average_interruptions: Option<f64>,
+}
+
+/// Read the history and print the report in the requested format
+pub fn run(args: &StatsArgs) -> io::Result<()> {
+    let since = args.since.as_deref().map(parse_date).transpose()?;
+    let until = args.until.as_deref().map(parse_date).transpose()?;
+
+    let entries = match history::get_history_path() {
+        Some(path) => history::load(path)?,
+        None => Vec::new(),
+    };
+    let report = build_report(&entries, since, until, args.by);
+
+    let mut out = io::stdout().lock();
+    if args.json {
+        let json = serde_json::to_string_pretty(&report).map_err(io::Error::other)?;
+        writeln!(out, "{json}")
+    } else if args.csv {
+        write_csv(&mut out, &report)
+    } else {
+        write_text(&mut out, &report)
+    }
+}
+
+fn parse_date(s: &str) -> io::Result<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid date {s} (expected YYYY-MM-DD): {e}"),
+        )
+    })
+}
+
+/// Summarise the work phases started between `since` and `until` (inclusive, local dates)
+pub fn build_report(
+    entries: &[Entry],
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+    by: StatsPeriod,
+) -> Report {
+    let work = entries.iter().filter(|e| {
+        let day = e.started_at.date_naive();
+        !e.kind.is_break()
+            && since.is_none_or(|since| day >= since)
+            && until.is_none_or(|until| day <= until)
+    });
+
+    let mut report = Report {
+        since,
+        until,
+        ..Report::default()
+    };
+    let mut periods: BTreeMap<String, PeriodStats> = BTreeMap::new();
+    let mut completed_days = BTreeSet::new();
+    let mut phases = 0u32;
+    let mut interruptions = 0u32;
+
+    for entry in work {
+        let day = entry.started_at.date_naive();
+        let key = period_key(day, by);
+        let period = periods.entry(key.clone()).or_insert_with(|| PeriodStats {
+            period: key,
+            ..PeriodStats::default()
+        });
+
+        let focus_secs = entry.actual_secs + entry.overtime_secs;
+        period.focus_secs += focus_secs;
+        report.focus_secs += focus_secs;
+        phases += 1;
+        interruptions += entry.interruptions;
+
+        match entry.outcome {
+            Outcome::Completed => {
+                period.completed += 1;
+                report.completed += 1;
+                completed_days.insert(day);
+            }
+            Outcome::Skipped => {
+                period.skipped += 1;
+                report.skipped += 1;
+            }
+            Outcome::Reset | Outcome::Interrupted => report.abandoned += 1,
+        }
+    }
+
+    report.periods = periods.into_values().collect();
+    let decided = report.completed + report.skipped;
+    report.completion_ratio = (decided > 0).then(|| report.completed as f64 / decided as f64);
+    report.average_interruptions = (phases > 0).then(|| interruptions as f64 / phases as f64);
+    report.longest_streak_days = longest_streak(&completed_days);
+    report
+}
+
+fn period_key(day: NaiveDate, by: StatsPeriod) -> String {
+    match by {
+        StatsPeriod::Day => day.format("%Y-%m-%d").to_string(),
+        StatsPeriod::Week => {
+            let week = day.iso_week();
+            format!("{}-W{:02}", week.year(), week.week())
+        }
+        StatsPeriod::Month => day.format("%Y-%m").to_string(),
+    }
+}
+
+fn longest_streak(days: &BTreeSet<NaiveDate>) -> u32 {
+    let mut longest = 0;
+    let mut current = 0;
+    let mut previous: Option<NaiveDate> = None;
+
+    for &day in days {
+        current = match previous {
+            Some(prev) if prev.succ_opt() == Some(day) => current + 1,
+            _ => 1,
+        };
+        longest = longest.max(current);
+        previous = Some(day);
+    }
+    longest
+}
+
+fn write_text<W>(out: &mut W, report: &Report) -> io::Result<()>
+where
+    W: Write,
+{
+    if report.periods.is_empty() {
+        return writeln!(out, "No work phases recorded");
+    }
+
+    writeln!(
+        out,
+        "{:<12} {:>8} {:>10} {:>8}",
+        "Period", "Focus", "Completed", "Skipped"
+    )?;
+    for period in &report.periods {
+        writeln!(
+            out,
+            "{:<12} {:>8} {:>10} {:>8}",
+            period.period,
+            format_focus(period.focus_secs),
+            period.completed,
+            period.skipped
+        )?;
+    }
+
+    writeln!(out)?;
+    writeln!(out, "Total focus:      {}", format_focus(report.focus_secs))?;
+    write!(
+        out,
+        "Completed:        {} (skipped {}, abandoned {})",
+        report.completed, report.skipped, report.abandoned
+    )?;
+    match report.completion_ratio {
+        Some(ratio) => writeln!(out, ", {:.0}% completed", ratio * 100.0)?,
+        None => writeln!(out)?,
+    }
+    writeln!(out, "Longest streak:   {} days", report.longest_streak_days)?;
+    if let Some(average) = report.average_interruptions {
+        writeln!(out, "Interruptions:    {average:.1} per work phase")?;
+    }
+    Ok(())
+}
+
+fn write_csv<W>(out: &mut W, report: &Report) -> io::Result<()>
+where
+    W: Write,
+{
+    writeln!(out, "period,focus_secs,completed,skipped")?;
+    for period in &report.periods {
+        writeln!(
+            out,
+            "{},{},{},{}",
+            period.period, period.focus_secs, period.completed, period.skipped
+        )?;
+    }
+    Ok(())
+}
+
+/// Format seconds as e.g. `1h05m` or `25m`
+fn format_focus(secs: u64) -> String {
+    let hours = secs / 3600;
+    let mins = (secs % 3600) / 60;
+    if hours > 0 {
+        format!("{hours}h{mins:02}m")
+    } else {
+        format!("{mins}m")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::PhaseKind;
+    use chrono::{Local, TimeZone};
+
+    fn entry(day: u32, kind: PhaseKind, outcome: Outcome, interruptions: u32) -> Entry {
+        let Some(started_at) = Local.with_ymd_and_hms(2026, 10, day, 9, 0, 0).single() else {
+            panic!("9am local time should be unambiguous");
+        };
+        Entry {
+            phase: kind.as_ref().to_string(),
+            kind,
+            started_at,
+            ended_at: started_at + chrono::Duration::minutes(25),
+            planned_secs: 1500,
+            actual_secs: if outcome == Outcome::Completed {
+                1500
+            } else {
+                600
+            },
+            overtime_secs: 0,
+            outcome,
+            interruptions,
+        }
+    }
+
+    fn history() -> Vec<Entry> {
+        vec![
+            entry(5, PhaseKind::Work, Outcome::Completed, 0),
+            entry(5, PhaseKind::Break, Outcome::Completed, 0),
+            entry(6, PhaseKind::Work, Outcome::Completed, 2),
+            entry(6, PhaseKind::Work, Outcome::Skipped, 1),
+            entry(7, PhaseKind::Work, Outcome::Completed, 0),
+            entry(9, PhaseKind::Work, Outcome::Completed, 0),
+            entry(12, PhaseKind::Work, Outcome::Reset, 1),
+        ]
+    }
+
+    #[test]
+    fn test_report_by_day() -> crate::error::Result<()> {
+        let report = build_report(&history(), None, None, StatsPeriod::Day);
+        assert_eq!(report.periods.len(), 5);
+        assert_eq!(report.periods[1].period, "2026-10-06");
+        assert_eq!(report.periods[1].focus_secs, 2100);
+        assert_eq!(report.periods[1].completed, 1);
+        assert_eq!(report.periods[1].skipped, 1);
+        assert_eq!(report.focus_secs, 4 * 1500 + 2 * 600);
+        assert_eq!(
+            (report.completed, report.skipped, report.abandoned),
+            (4, 1, 1)
+        );
+        assert_eq!(report.completion_ratio, Some(0.8));
+        assert_eq!(report.longest_streak_days, 3);
+        assert_eq!(report.average_interruptions, Some(4.0 / 6.0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_report_range_and_grouping() -> crate::error::Result<()> {
+        let since = NaiveDate::from_ymd_opt(2026, 10, 6);
+        let until = NaiveDate::from_ymd_opt(2026, 10, 9);
+
+        let report = build_report(&history(), since, until, StatsPeriod::Week);
+        let periods: Vec<_> = report.periods.iter().map(|p| p.period.as_str()).collect();
+        assert_eq!(periods, vec!["2026-W41"]);
+        assert_eq!(report.completed, 3);
+
+        let report = build_report(&history(), None, None, StatsPeriod::Month);
+        assert_eq!(report.periods.len(), 1);
+        assert_eq!(report.periods[0].period, "2026-10");
+        Ok(())
+    }
+
+    #[test]
+    fn test_output_formats() -> crate::error::Result<()> {
+        let report = build_report(&history(), None, None, StatsPeriod::Day);
+
+        let mut csv = Vec::new();
+        write_csv(&mut csv, &report)?;
+        let csv = String::from_utf8_lossy(&csv);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("period,focus_secs,completed,skipped"));
+        assert_eq!(lines.next(), Some("2026-10-05,1500,1,0"));
+
+        let mut text = Vec::new();
+        write_text(&mut text, &report)?;
+        let text = String::from_utf8_lossy(&text);
+        assert!(text.contains("Total focus:      2h00m"));
+        assert!(text.contains("80% completed"));
+        assert_eq!(format_focus(1500), "25m");
+        Ok(())
+    }
+}
@@ -1,15 +1,25 @@
+use std::ops::Add;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Local, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumString, VariantNames};
+
 use crate::config::Config;
+use crate::history::{Entry, Outcome};
+use crate::schedule::{PhaseKind, PhaseSpec, Schedule};
+use crate::state::RestorePolicy;
 
-/// The current phase of the pomodoro cycle
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Phase {
-    Work,
-    ShortBreak,
-    LongBreak,
-}
+/// Wall-clock time running ahead of monotonic time by more than this is
+/// treated as a suspend (or a manual clock change) rather than drift
+pub const JUMP_THRESHOLD: Duration = Duration::from_secs(5);
 
-/// Represents a phase transition after tick
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How long the key celebrates after the daily goal is reached
+pub const GOAL_CELEBRATION: Duration = Duration::from_secs(60);
+
+/// Represents a phase transition after an update
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Transition {
     /// No transition occurred
     None,
@@ -19,197 +29,937 @@ pub enum Transition {
     BreakComplete,
 }
 
-impl Phase {
-    pub fn is_break(self) -> bool {
-        matches!(self, Phase::ShortBreak | Phase::LongBreak)
+/// Something observable that happened to the timer, queued for subscribers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TimerEvent {
+    /// The remaining time changed by at least a whole second
+    Tick,
+    /// The timer moved to the next phase
+    Transition {
+        transition: Transition,
+        /// True if the phase was cut short by `skip`
+        skipped: bool,
+    },
+    /// The timer was paused
+    Pause,
+    /// The current phase started running for the first time
+    Start { kind: PhaseKind },
+    /// The timer was started or resumed
+    Resume,
+    /// A work phase ran out and is now counting overtime
+    Overtime,
+    /// The timer was reset to the beginning of the schedule
+    Reset,
+    /// The current phase was lengthened, shortened or replaced by hand
+    Adjust,
+}
+
+/// What to do with the time the machine spent suspended while the timer was running
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, AsRefStr, VariantNames)]
+#[strum(serialize_all = "snake_case")]
+pub enum SuspendPolicy {
+    /// Suspended time counts, the phase keeps running against the wall clock
+    #[default]
+    Continue,
+    /// Suspended time is discarded and the timer is paused on resume
+    Pause,
+}
+
+/// What happens when a work phase runs out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, AsRefStr, VariantNames)]
+#[strum(serialize_all = "snake_case")]
+pub enum OvertimePolicy {
+    /// Move on to the next phase straight away
+    #[default]
+    Off,
+    /// Keep counting past zero until the phase is skipped
+    Count,
+    /// Keep counting, and add the overtime to the following break
+    AddToBreak,
+}
+
+/// How the length of work phases is decided
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, AsRefStr, VariantNames)]
+#[strum(serialize_all = "snake_case")]
+pub enum TimerMode {
+    /// Every phase counts down from its configured length
+    #[default]
+    Pomodoro,
+    /// Work phases count up until advanced; the break that follows is
+    /// sized from how long the work ran
+    Flowtime,
+}
+
+/// One row of the flowtime break table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlowtimeStep {
+    /// Applies to work shorter than this (None: any length)
+    pub under: Option<Duration>,
+    pub break_length: Duration,
+}
+
+/// How flowtime breaks are sized from the work before them
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowtimeBreaks {
+    /// Rows tried in order; the first one that applies wins
+    pub steps: Vec<FlowtimeStep>,
+    /// Break time per unit of work time, used when no row applies
+    pub ratio: f32,
+}
+
+impl FlowtimeBreaks {
+    /// Shortest break handed out, however little work preceded it
+    const MIN_BREAK: Duration = Duration::from_secs(60);
+
+    /// Length of the break earned by `worked`
+    pub fn break_for(&self, worked: Duration) -> Duration {
+        self.steps
+            .iter()
+            .find(|step| step.under.is_none_or(|under| worked < under))
+            .map(|step| step.break_length)
+            .unwrap_or_else(|| Duration::from_secs(worked.mul_f32(self.ratio).as_secs()))
+            .max(Self::MIN_BREAK)
     }
 }
 
+/// A discontinuity between the monotonic and the wall clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockJump {
+    /// Wall clock moved ahead of monotonic time (suspend/resume or clock set forward)
+    Forward(Duration),
+    /// Wall clock moved backwards (clock set back)
+    Backward(Duration),
+}
+
+/// A point in time on both the monotonic and the wall clock
+///
+/// The monotonic clock is immune to clock changes but stops while the
+/// machine is suspended; the wall clock is used to notice the suspend.
+#[derive(Debug, Clone, Copy)]
+pub struct Stamp {
+    pub mono: Instant,
+    pub wall: SystemTime,
+}
+
+impl Stamp {
+    pub fn now() -> Self {
+        Stamp {
+            mono: Instant::now(),
+            wall: SystemTime::now(),
+        }
+    }
+
+    /// Time elapsed from `earlier` to `self`
+    ///
+    /// Uses the monotonic clock unless the wall clock is ahead by more than
+    /// [`JUMP_THRESHOLD`], in which case the machine was suspended in between
+    /// and the wall clock is the one that kept counting.
+    pub fn since(self, earlier: Stamp) -> Duration {
+        let mono = self.mono.saturating_duration_since(earlier.mono);
+        match detect_jump(earlier, self) {
+            Some(ClockJump::Forward(gap)) => mono + gap,
+            _ => mono,
+        }
+    }
+}
+
+impl Add<Duration> for Stamp {
+    type Output = Stamp;
+
+    fn add(self, rhs: Duration) -> Stamp {
+        Stamp {
+            mono: self.mono + rhs,
+            wall: self.wall + rhs,
+        }
+    }
+}
+
+/// Compare how far each clock moved between two stamps
+pub fn detect_jump(earlier: Stamp, later: Stamp) -> Option<ClockJump> {
+    let mono = later.mono.saturating_duration_since(earlier.mono);
+    match later.wall.duration_since(earlier.wall) {
+        Ok(wall) if wall > mono + JUMP_THRESHOLD => Some(ClockJump::Forward(wall - mono)),
+        Ok(_) => None,
+        Err(e) => {
+            let gap = e.duration() + mono;
+            (gap > JUMP_THRESHOLD).then_some(ClockJump::Backward(gap))
+        }
+    }
+}
+
+/// Completed work phases so far today against the daily goal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GoalProgress {
+    pub done: u32,
+    pub target: u32,
+    /// True for [`GOAL_CELEBRATION`] after the goal was reached
+    pub celebrating: bool,
+}
+
+impl GoalProgress {
+    /// Share of the goal completed, 0.0 to 1.0
+    pub fn ratio(&self) -> f32 {
+        if self.target == 0 {
+            return 0.0;
+        }
+        (self.done as f32 / self.target as f32).min(1.0)
+    }
+}
+
+/// Persisted timer state, written on transitions and pauses
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Name of the current phase
+    pub phase: String,
+    /// Position of the current phase in the schedule
+    pub index: usize,
+    pub elapsed_secs: u64,
+    pub iterations: u8,
+    pub sessions_completed: u32,
+    pub running: bool,
+    /// Wall-clock time the snapshot was taken (seconds since the Unix epoch)
+    pub saved_at: u64,
+    /// Adjusted length of the current phase, if it was extended, shortened or set
+    #[serde(default)]
+    pub length_override_secs: Option<u64>,
+    /// When the current phase was first started (seconds since the Unix epoch)
+    #[serde(default)]
+    pub phase_started_at: Option<u64>,
+    /// Times the current phase has been paused
+    #[serde(default)]
+    pub pauses: u32,
+    /// Day the daily goal count belongs to
+    #[serde(default)]
+    pub goal_day: Option<NaiveDate>,
+    /// Work phases completed on `goal_day`
+    #[serde(default)]
+    pub goal_done: u32,
+    /// When the daily goal was reached (seconds since the Unix epoch)
+    #[serde(default)]
+    pub goal_reached_at: Option<u64>,
+}
+
 /// Pomodoro timer state machine
+///
+/// Time is never counted in ticks: while running, the elapsed time of the
+/// current phase is derived from the clock as `banked` plus the time since
+/// `segment_start`, so it cannot drift no matter how irregularly it is polled.
 #[derive(Debug, Clone)]
 pub struct Timer {
-    /// Current phase
-    phase: Phase,
-    /// Elapsed seconds in current phase
-    elapsed_secs: u64,
-    /// Duration settings in seconds
-    work_secs: u64,
-    short_break_secs: u64,
-    long_break_secs: u64,
-    /// Work iterations completed (0-3, resets after long break)
+    /// Phases to cycle through
+    schedule: Schedule,
+    /// Position of the current phase in the schedule
+    index: usize,
+    /// Elapsed time in current phase, as of the last update
+    elapsed: Duration,
+    /// Elapsed time accumulated before the current running segment
+    banked: Duration,
+    /// When the current running segment started (None while paused)
+    segment_start: Option<Stamp>,
+    /// Length of the current phase if adjusted by hand, cleared on the next phase
+    length_override: Option<Duration>,
+    /// When the current phase was first started (None until it has run)
+    phase_started: Option<SystemTime>,
+    /// Times the current phase has been paused
+    pauses: u32,
+    /// When the timer was last updated, used to detect clock jumps
+    last_update: Option<Stamp>,
+    /// Work iterations completed (resets when the schedule wraps around)
     iterations: u8,
-    /// Total completed pomodoro sessions
+    /// Total completed pomodoro sessions (full passes through the schedule)
     sessions_completed: u32,
-    /// Whether the timer is running
-    running: bool,
-    /// Auto-start work after break
-    auto_start_work: bool,
-    /// Auto-start break after work
-    auto_start_break: bool,
+    /// What to do when a suspend is detected while running
+    on_suspend: SuspendPolicy,
+    /// What to do when a work phase runs out
+    overtime: OvertimePolicy,
+    /// Whether work phases count down or up
+    mode: TimerMode,
+    /// Break sizing for [`TimerMode::Flowtime`]
+    flowtime: FlowtimeBreaks,
+    /// Events not yet collected by [`Timer::drain_events`]
+    events: Vec<TimerEvent>,
+    /// Remaining and overtime seconds reported by the last tick
+    last_tick: Option<(u64, u64)>,
+    /// Finished phases not yet collected by [`Timer::drain_history`]
+    history: Vec<Entry>,
+    /// Completed work phases to aim for each day (0 disables the goal)
+    daily_goal: u32,
+    /// Local time at which a new goal day begins
+    day_starts_at: NaiveTime,
+    /// Day `goal_done` was counted on (None until a work phase completes)
+    goal_day: Option<NaiveDate>,
+    /// Work phases completed on `goal_day`; not cleared by [`Timer::reset`]
+    goal_done: u32,
+    /// When the daily goal was last reached
+    goal_reached_at: Option<SystemTime>,
 }
 
 impl Timer {
     pub fn new(config: &Config) -> Self {
         Timer {
-            phase: Phase::Work,
-            elapsed_secs: 0,
-            work_secs: config.work * 60,
-            short_break_secs: config.short_break * 60,
-            long_break_secs: config.long_break * 60,
+            schedule: config.schedule.clone(),
+            index: 0,
+            elapsed: Duration::ZERO,
+            banked: Duration::ZERO,
+            segment_start: None,
+            length_override: None,
+            phase_started: None,
+            pauses: 0,
+            last_update: None,
             iterations: 0,
             sessions_completed: 0,
-            running: false,
-            auto_start_work: config.auto_start_work,
-            auto_start_break: config.auto_start_break,
+            on_suspend: config.on_suspend,
+            overtime: config.overtime,
+            mode: config.mode,
+            flowtime: config.flowtime.clone(),
+            events: Vec::new(),
+            last_tick: None,
+            history: Vec::new(),
+            daily_goal: config.daily_goal,
+            day_starts_at: config.day_starts_at,
+            goal_day: None,
+            goal_done: 0,
+            goal_reached_at: None,
         }
     }
 
-    pub fn phase(&self) -> Phase {
-        self.phase
+    /// The current phase
+    pub fn phase(&self) -> &PhaseSpec {
+        self.schedule.get(self.index)
+    }
+
+    /// Position of the current phase in the schedule
+    pub fn phase_index(&self) -> usize {
+        self.index
     }
 
     pub fn is_running(&self) -> bool {
-        self.running
+        self.segment_start.is_some()
     }
 
     /// Returns true if timer is at a phase boundary (elapsed = 0)
     pub fn at_phase_boundary(&self) -> bool {
-        self.elapsed_secs == 0
+        self.elapsed.is_zero()
     }
 
     pub fn iterations(&self) -> u8 {
         self.iterations
     }
 
+    /// Number of work iterations in a full pass through the schedule
+    pub fn works_per_cycle(&self) -> u8 {
+        self.schedule.work_count()
+    }
+
     pub fn sessions_completed(&self) -> u32 {
         self.sessions_completed
     }
 
-    /// Get the duration of the current phase in seconds
-    pub fn current_duration(&self) -> u64 {
-        match self.phase {
-            Phase::Work => self.work_secs,
-            Phase::ShortBreak => self.short_break_secs,
-            Phase::LongBreak => self.long_break_secs,
+    /// Progress towards the daily goal, or None if no goal is set
+    pub fn goal_progress(&self) -> Option<GoalProgress> {
+        self.goal_progress_at(SystemTime::now())
+    }
+
+    pub fn goal_progress_at(&self, now: SystemTime) -> Option<GoalProgress> {
+        if self.daily_goal == 0 {
+            return None;
+        }
+        let done = self.completed_today_at(now);
+        let celebrating = done >= self.daily_goal
+            && self
+                .goal_reached_at
+                .and_then(|at| now.duration_since(at).ok())
+                .is_some_and(|since| since < GOAL_CELEBRATION);
+        Some(GoalProgress {
+            done,
+            target: self.daily_goal,
+            celebrating,
+        })
+    }
+
+    /// Work phases completed so far today, whether or not a daily goal is set
+    pub fn completed_today(&self) -> u32 {
+        self.completed_today_at(SystemTime::now())
+    }
+
+    pub fn completed_today_at(&self, now: SystemTime) -> u32 {
+        // The count belongs to an earlier day until the first completion of today
+        if self.goal_day == Some(self.goal_day_of(now)) {
+            self.goal_done
+        } else {
+            0
         }
     }
 
+    /// Get the duration of the current phase in seconds, including any adjustment
+    pub fn current_duration(&self) -> u64 {
+        self.current_length().as_secs()
+    }
+
     /// Get elapsed seconds in current phase
     pub fn elapsed_secs(&self) -> u64 {
-        self.elapsed_secs
+        self.elapsed.as_secs()
     }
 
     /// Get progress ratio (0.0 to 1.0) for current phase
+    /// A phase counting up fills towards its configured length
     pub fn progress_ratio(&self) -> f32 {
-        let duration = self.current_duration();
+        let duration = if self.counts_up() {
+            self.phase().duration_secs
+        } else {
+            self.current_duration()
+        };
         if duration == 0 {
             return 0.0;
         }
-        (self.elapsed_secs as f32 / duration as f32).clamp(0.0, 1.0)
+        (self.elapsed.as_secs_f32() / duration as f32).clamp(0.0, 1.0)
     }
 
     /// Get remaining time in seconds
     pub fn remaining_secs(&self) -> u64 {
-        self.current_duration().saturating_sub(self.elapsed_secs)
+        self.current_duration().saturating_sub(self.elapsed_secs())
     }
 
-    /// Format remaining time as MM:SS or HH:MM:SS
-    pub fn remaining_formatted(&self) -> String {
-        let secs = self.remaining_secs();
-        let hours = secs / 3600;
-        let mins = (secs % 3600) / 60;
-        let secs = secs % 60;
+    /// True if the current phase has no length and counts up until advanced
+    pub fn counts_up(&self) -> bool {
+        self.mode == TimerMode::Flowtime && !self.phase().is_break()
+    }
+
+    /// True if a work phase has run out and is counting overtime
+    pub fn is_overtime(&self) -> bool {
+        self.overtime != OvertimePolicy::Off
+            && !self.counts_up()
+            && !self.phase().is_break()
+            && self.elapsed >= self.current_length()
+    }
 
-        if hours > 0 {
-            format!("{hours}:{mins:02}:{secs:02}")
+    /// True if a phase counting down has `within` or less left to run
+    /// Always false for a zero `within`, which disables the warning
+    pub fn ends_within(&self, within: Duration) -> bool {
+        !within.is_zero()
+            && !self.counts_up()
+            && !self.is_overtime()
+            && self.remaining_secs() <= within.as_secs()
+    }
+
+    /// Get the seconds run past the end of the current phase
+    pub fn overtime_secs(&self) -> u64 {
+        if self.is_overtime() {
+            (self.elapsed - self.current_length()).as_secs()
+        } else {
+            0
+        }
+    }
+
+    /// Format remaining time as MM:SS or HH:MM:SS, overtime as +MM:SS, or
+    /// the elapsed time of a phase counting up
+    pub fn remaining_formatted(&self) -> String {
+        if self.counts_up() {
+            format_secs(self.elapsed_secs())
+        } else if self.is_overtime() {
+            format!("+{}", format_secs(self.overtime_secs()))
         } else {
-            format!("{mins:02}:{secs:02}")
+            format_secs(self.remaining_secs())
         }
     }
 
+    /// Position of the phase that follows the current one
+    pub fn next_phase_index(&self) -> usize {
+        (self.index + 1) % self.schedule.phases().len()
+    }
+
+    /// The phase that follows the current one
+    pub fn next_phase(&self) -> &PhaseSpec {
+        self.schedule.get(self.next_phase_index())
+    }
+
     /// Toggle between running and paused
     pub fn toggle(&mut self) {
-        self.running = !self.running;
+        self.toggle_at(Stamp::now());
+    }
+
+    pub fn toggle_at(&mut self, now: Stamp) {
+        if self.is_running() {
+            self.pause_at(now);
+        } else {
+            self.start_at(now);
+        }
     }
 
     /// Start the timer
     pub fn start(&mut self) {
-        self.running = true;
+        self.start_at(Stamp::now());
+    }
+
+    pub fn start_at(&mut self, now: Stamp) {
+        if self.segment_start.is_none() {
+            self.segment_start = Some(now);
+            self.last_update = Some(now);
+            self.events.push(TimerEvent::Resume);
+            if self.phase_started.is_none() {
+                self.phase_started = Some(now.wall);
+                self.push_start();
+            }
+        }
     }
 
     /// Pause the timer
     pub fn pause(&mut self) {
-        self.running = false;
+        self.pause_at(Stamp::now());
+    }
+
+    pub fn pause_at(&mut self, now: Stamp) {
+        // Bring elapsed up to date first so the time run so far is banked
+        self.update_at(now);
+        if self.segment_start.take().is_some() {
+            self.banked = self.elapsed;
+            self.pauses += 1;
+            self.events.push(TimerEvent::Pause);
+        }
     }
 
     /// Reset to initial state
     pub fn reset(&mut self) {
-        self.phase = Phase::Work;
-        self.elapsed_secs = 0;
+        self.reset_at(Stamp::now());
+    }
+
+    pub fn reset_at(&mut self, now: Stamp) {
+        // The phase is logged with the time it actually ran
+        self.update_at(now);
+        self.record(Outcome::Reset, now);
+        self.index = 0;
+        self.elapsed = Duration::ZERO;
+        self.banked = Duration::ZERO;
+        self.segment_start = None;
+        self.length_override = None;
         self.iterations = 0;
         self.sessions_completed = 0;
-        self.running = false;
+        self.events.push(TimerEvent::Reset);
+    }
+
+    /// Lengthen the current phase
+    /// Returns false if `by` is zero or the phase counts up
+    pub fn extend(&mut self, by: Duration) -> bool {
+        if by.is_zero() || self.counts_up() {
+            return false;
+        }
+        self.length_override = Some(self.current_length() + by);
+        self.events.push(TimerEvent::Adjust);
+        true
+    }
+
+    /// Shorten the current phase; a phase shortened past its elapsed time
+    /// completes on the next update
+    /// Returns false if the phase cannot get any shorter
+    pub fn shorten(&mut self, by: Duration) -> bool {
+        let length = self.current_length().saturating_sub(by);
+        if length == self.current_length() {
+            return false;
+        }
+        self.length_override = Some(length);
+        self.events.push(TimerEvent::Adjust);
+        true
+    }
+
+    /// Set the time remaining in the current phase
+    /// Returns false if it already has that long left or the phase counts up
+    pub fn set_remaining(&mut self, remaining: Duration) -> bool {
+        self.set_remaining_at(remaining, Stamp::now())
+    }
+
+    pub fn set_remaining_at(&mut self, remaining: Duration, now: Stamp) -> bool {
+        // Elapsed must be current for the new length to leave exactly `remaining`
+        self.update_at(now);
+        let length = self.elapsed + remaining;
+        if length == self.current_length() || self.counts_up() {
+            return false;
+        }
+        self.length_override = Some(length);
+        self.events.push(TimerEvent::Adjust);
+        true
     }
 
-    /// Skip to the next phase without waiting
+    /// Jump to the start of the first phase called `name`, keeping the running state
+    /// Iterations and sessions are left alone, as nothing was completed
+    /// Returns false if the schedule has no such phase
+    pub fn jump_to<S>(&mut self, name: S) -> bool
+    where
+        S: AsRef<str>,
+    {
+        self.jump_to_at(name, Stamp::now())
+    }
+
+    pub fn jump_to_at<S>(&mut self, name: S, now: Stamp) -> bool
+    where
+        S: AsRef<str>,
+    {
+        let Some(index) = self.schedule.position(name) else {
+            return false;
+        };
+        // The phase left behind is logged with the time it actually ran
+        self.update_at(now);
+        self.record(Outcome::Skipped, now);
+        self.index = index;
+        self.elapsed = Duration::ZERO;
+        self.banked = Duration::ZERO;
+        self.length_override = None;
+        self.events.push(TimerEvent::Adjust);
+        if self.is_running() {
+            self.segment_start = Some(now);
+            self.last_update = Some(now);
+            self.phase_started = Some(now.wall);
+            self.push_start();
+        }
+        true
+    }
+
+    /// Skip to the next phase without waiting; a phase in overtime counts as completed
     /// Returns the type of transition that occurred, or None if paused at phase boundary
     pub fn skip(&mut self) -> Transition {
-        if !self.running && self.at_phase_boundary() {
+        self.skip_at(Stamp::now())
+    }
+
+    pub fn skip_at(&mut self, now: Stamp) -> Transition {
+        // The phase on show may have ended since the last update; if so that
+        // transition is the skip, rather than skipping the phase after it too
+        if let Some(&transition) = self.update_at(now).last() {
+            return transition;
+        }
+        if !self.is_running() && self.at_phase_boundary() {
             return Transition::None;
         }
-        self.transition_to_next_phase()
+        let outcome = if self.is_overtime() || self.counts_up() {
+            Outcome::Completed
+        } else {
+            Outcome::Skipped
+        };
+        self.transition_to_next_phase(outcome, now)
     }
 
-    /// Advance time by one second, handling phase transitions
-    /// Returns the type of transition that occurred (if any)
-    pub fn tick(&mut self) -> Transition {
-        if !self.running {
-            return Transition::None;
+    /// Bring the timer up to date with the clock, handling phase transitions
+    /// Returns every transition that occurred since the last update, in order
+    pub fn update(&mut self) -> Vec<Transition> {
+        self.update_at(Stamp::now())
+    }
+
+    pub fn update_at(&mut self, now: Stamp) -> Vec<Transition> {
+        let mut transitions = Vec::new();
+        let Some(start) = self.segment_start else {
+            return transitions;
+        };
+
+        let was_overtime = self.is_overtime();
+        let jump = self.last_update.and_then(|last| detect_jump(last, now));
+        self.last_update = Some(now);
+
+        if let Some(jump) = jump {
+            tracing::info!(
+                ?jump,
+                policy = self.on_suspend.as_ref(),
+                "Clock jump detected"
+            );
         }
 
-        self.elapsed_secs += 1;
+        if self.on_suspend == SuspendPolicy::Pause && matches!(jump, Some(ClockJump::Forward(_))) {
+            // Count only the time the machine was awake, then stop
+            self.elapsed = self.banked + now.mono.saturating_duration_since(start.mono);
+            self.banked = self.elapsed;
+            self.segment_start = None;
+            self.pauses += 1;
+            self.events.push(TimerEvent::Pause);
+        } else {
+            self.elapsed = self.banked + now.since(start);
+        }
 
-        if self.elapsed_secs >= self.current_duration() {
-            return self.transition_to_next_phase();
+        // A long gap can span several phases when auto-start is enabled
+        while !self.counts_up() && self.elapsed >= self.current_length() {
+            if self.is_overtime() {
+                if !was_overtime {
+                    self.events.push(TimerEvent::Overtime);
+                }
+                break;
+            }
+            let overflow = self.elapsed - self.current_length();
+            transitions.push(self.transition_to_next_phase(Outcome::Completed, now));
+            if self.is_running() {
+                self.banked = overflow;
+                self.elapsed = overflow;
+            } else {
+                break;
+            }
         }
 
-        Transition::None
+        let tick = (self.remaining_secs(), self.elapsed_secs());
+        if self.is_running() && self.last_tick != Some(tick) {
+            self.last_tick = Some(tick);
+            self.events.push(TimerEvent::Tick);
+        }
+
+        transitions
     }
 
-    fn transition_to_next_phase(&mut self) -> Transition {
-        self.elapsed_secs = 0;
-        let from_phase = self.phase;
+    /// Phase transitions among the events not yet taken by [`Timer::drain_events`]
+    pub fn queued_transitions(&self) -> usize {
+        self.events
+            .iter()
+            .filter(|event| matches!(event, TimerEvent::Transition { .. }))
+            .count()
+    }
 
-        match self.phase {
-            Phase::Work => {
-                self.iterations += 1;
+    /// Take the events queued since the last call, oldest first
+    pub fn drain_events(&mut self) -> Vec<TimerEvent> {
+        std::mem::take(&mut self.events)
+    }
 
-                if self.iterations >= 4 {
-                    self.phase = Phase::LongBreak;
-                } else {
-                    self.phase = Phase::ShortBreak;
-                }
+    /// Take the phases finished since the last call, oldest first
+    pub fn drain_history(&mut self) -> Vec<Entry> {
+        std::mem::take(&mut self.history)
+    }
 
-                self.running = self.auto_start_break;
-            }
-            Phase::ShortBreak => {
-                self.phase = Phase::Work;
-                self.running = self.auto_start_work;
-            }
-            Phase::LongBreak => {
-                self.phase = Phase::Work;
-                self.iterations = 0;
-                self.sessions_completed += 1;
-                self.running = self.auto_start_work;
-            }
+    /// Capture the current state for persistence
+    pub fn snapshot(&self) -> Snapshot {
+        let saved_at = self
+            .last_update
+            .filter(|_| self.is_running())
+            .map(|stamp| stamp.wall)
+            .unwrap_or_else(SystemTime::now);
+
+        Snapshot {
+            phase: self.phase().name.clone(),
+            index: self.index,
+            elapsed_secs: self.elapsed_secs(),
+            iterations: self.iterations,
+            sessions_completed: self.sessions_completed,
+            running: self.is_running(),
+            saved_at: unix_secs(saved_at),
+            length_override_secs: self.length_override.map(|d| d.as_secs()),
+            phase_started_at: self.phase_started.map(unix_secs),
+            pauses: self.pauses,
+            goal_day: self.goal_day,
+            goal_done: self.goal_done,
+            goal_reached_at: self.goal_reached_at.map(unix_secs),
         }
+    }
 
-        if from_phase == Phase::Work {
-            Transition::WorkComplete
+    /// Restore state from a snapshot, accounting for the downtime according to `policy`
+    pub fn restore(&mut self, snapshot: &Snapshot, policy: RestorePolicy, now: Stamp) {
+        // Phases already completed today count whatever happens to the saved phase
+        self.goal_day = snapshot.goal_day;
+        self.goal_done = snapshot.goal_done;
+        // The celebration runs on like the phase would: through the downtime
+        // when catching up, picking up where it left off when paused
+        let downtime = unix_secs(now.wall).saturating_sub(snapshot.saved_at);
+        self.goal_reached_at = match policy {
+            RestorePolicy::CatchUp => snapshot.goal_reached_at.map(from_unix_secs),
+            RestorePolicy::Pause => snapshot
+                .goal_reached_at
+                .map(|at| from_unix_secs(at.saturating_add(downtime))),
+            RestorePolicy::Discard => None,
+        };
+
+        if policy == RestorePolicy::Discard {
+            self.record_interrupted(snapshot);
+            return;
+        }
+
+        // Prefer the saved position; fall back to the phase name if the schedule changed
+        let index = if self
+            .schedule
+            .phases()
+            .get(snapshot.index)
+            .is_some_and(|p| p.name == snapshot.phase)
+        {
+            snapshot.index
+        } else if let Some(index) = self.schedule.position(&snapshot.phase) {
+            index
         } else {
+            tracing::warn!(
+                phase = snapshot.phase,
+                "Saved phase is not in the schedule, starting fresh"
+            );
+            return;
+        };
+
+        self.index = index;
+        self.elapsed = Duration::from_secs(snapshot.elapsed_secs);
+        self.banked = self.elapsed;
+        self.segment_start = None;
+        self.length_override = snapshot.length_override_secs.map(Duration::from_secs);
+        self.phase_started = snapshot.phase_started_at.map(from_unix_secs);
+        self.pauses = snapshot.pauses;
+        self.iterations = snapshot.iterations;
+        self.sessions_completed = snapshot.sessions_completed;
+
+        if snapshot.running && policy == RestorePolicy::CatchUp {
+            self.banked += Duration::from_secs(downtime);
+            self.start_at(now);
+            self.update_at(now);
+        }
+    }
+
+    /// Planned length of the current phase; zero for a phase counting up
+    fn current_length(&self) -> Duration {
+        if self.counts_up() {
+            return Duration::ZERO;
+        }
+        self.length_override
+            .unwrap_or_else(|| Duration::from_secs(self.phase().duration_secs))
+    }
+
+    /// Local date a point in time counts towards, with days starting at `day_starts_at`
+    fn goal_day_of(&self, time: SystemTime) -> NaiveDate {
+        let local: DateTime<Local> = time.into();
+        (local.naive_local() - (self.day_starts_at - NaiveTime::MIN)).date()
+    }
+
+    /// Count a completed work phase towards the daily goal
+    fn count_towards_goal(&mut self, now: SystemTime) {
+        let day = self.goal_day_of(now);
+        if self.goal_day != Some(day) {
+            self.goal_day = Some(day);
+            self.goal_done = 0;
+        }
+        self.goal_done += 1;
+        if self.goal_done == self.daily_goal {
+            self.goal_reached_at = Some(now);
+        }
+    }
+
+    /// Queue a history entry for the current phase, if it has run at all
+    fn record(&mut self, outcome: Outcome, now: Stamp) {
+        if outcome == Outcome::Completed && !self.phase().is_break() {
+            self.count_towards_goal(now.wall);
+        }
+        let Some(started) = self.phase_started.take() else {
+            return;
+        };
+        let phase = self.phase();
+        let entry = Entry {
+            phase: phase.name.clone(),
+            kind: phase.kind,
+            started_at: started.into(),
+            ended_at: now.wall.into(),
+            planned_secs: self.current_duration(),
+            actual_secs: if self.counts_up() {
+                self.elapsed_secs()
+            } else {
+                self.elapsed.min(self.current_length()).as_secs()
+            },
+            overtime_secs: self.overtime_secs(),
+            outcome,
+            interruptions: self.pauses,
+        };
+        self.history.push(entry);
+        self.pauses = 0;
+    }
+
+    /// Queue an interrupted entry for a saved phase that will not be resumed
+    fn record_interrupted(&mut self, snapshot: &Snapshot) {
+        let Some(started) = snapshot.phase_started_at else {
+            return;
+        };
+        let Some(phase) = self
+            .schedule
+            .position(&snapshot.phase)
+            .map(|index| self.schedule.get(index))
+        else {
+            return;
+        };
+        let planned_secs = snapshot.length_override_secs.unwrap_or(phase.duration_secs);
+        let entry = Entry {
+            phase: phase.name.clone(),
+            kind: phase.kind,
+            started_at: from_unix_secs(started).into(),
+            ended_at: from_unix_secs(snapshot.saved_at).into(),
+            planned_secs,
+            actual_secs: snapshot.elapsed_secs.min(planned_secs),
+            overtime_secs: snapshot.elapsed_secs.saturating_sub(planned_secs),
+            outcome: Outcome::Interrupted,
+            interruptions: snapshot.pauses,
+        };
+        self.history.push(entry);
+    }
+
+    fn transition_to_next_phase(&mut self, outcome: Outcome, now: Stamp) -> Transition {
+        let overtime = Duration::from_secs(self.overtime_secs());
+        let counted_up = self.counts_up().then_some(self.elapsed);
+        self.record(outcome, now);
+        self.elapsed = Duration::ZERO;
+        self.banked = Duration::ZERO;
+        self.length_override = None;
+        let finished_break = self.phase().is_break();
+
+        if !finished_break {
+            self.iterations = self.iterations.saturating_add(1);
+        }
+
+        self.index += 1;
+        if self.index >= self.schedule.phases().len() {
+            // Completing the last phase completes a session
+            self.index = 0;
+            self.iterations = 0;
+            self.sessions_completed += 1;
+        }
+
+        if self.overtime == OvertimePolicy::AddToBreak
+            && !overtime.is_zero()
+            && self.phase().is_break()
+        {
+            self.length_override = Some(self.current_length() + overtime);
+        }
+        if let Some(worked) = counted_up
+            && self.phase().is_break()
+        {
+            self.length_override = Some(self.flowtime.break_for(worked));
+        }
+
+        let transition = if finished_break {
             Transition::BreakComplete
+        } else {
+            Transition::WorkComplete
+        };
+        self.events.push(TimerEvent::Transition {
+            transition,
+            skipped: outcome == Outcome::Skipped,
+        });
+
+        self.segment_start = self.phase().auto_start.then_some(now);
+        self.phase_started = self.segment_start.map(|start| start.wall);
+        if self.phase_started.is_some() {
+            self.push_start();
         }
+        transition
     }
+
+    fn push_start(&mut self) {
+        self.events.push(TimerEvent::Start {
+            kind: self.phase().kind,
+        });
+    }
+}
+
+/// Format a number of seconds as MM:SS or HH:MM:SS
+pub fn format_secs(secs: u64) -> String {
+    let hours = secs / 3600;
+    let mins = (secs % 3600) / 60;
+    let secs = secs % 60;
+
+    if hours > 0 {
+        format!("{hours}:{mins:02}:{secs:02}")
+    } else {
+        format!("{mins:02}:{secs:02}")
+    }
+}
+
+fn from_unix_secs(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
 
 #[cfg(test)]
@@ -228,10 +978,16 @@ auto_start_break = false
         Ok(verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build())
     }
 
+    /// Advance the clock by `secs` seconds and update the timer
+    fn advance(timer: &mut Timer, now: &mut Stamp, secs: u64) -> Vec<Transition> {
+        *now = *now + Duration::from_secs(secs);
+        timer.update_at(*now)
+    }
+
     #[test]
     fn test_new_timer() -> crate::error::Result<()> {
         let timer = Timer::new(&test_config()?);
-        assert_eq!(timer.phase(), Phase::Work);
+        assert_eq!(timer.phase().name, "work");
         assert!(!timer.is_running());
         assert_eq!(timer.iterations(), 0);
         assert_eq!(timer.sessions_completed(), 0);
@@ -260,26 +1016,57 @@ auto_start_break = false
     }
 
     #[test]
-    fn test_tick_advances_time() -> crate::error::Result<()> {
+    fn test_update_advances_time() -> crate::error::Result<()> {
         let mut timer = Timer::new(&test_config()?);
-        timer.start();
+        let mut now = Stamp::now();
+        timer.start_at(now);
         let remaining_before = timer.remaining_secs();
-        timer.tick();
+        advance(&mut timer, &mut now, 1);
         assert_eq!(timer.remaining_secs(), remaining_before - 1);
         Ok(())
     }
 
+    #[test]
+    fn test_sub_second_updates_do_not_drift() -> crate::error::Result<()> {
+        let mut timer = Timer::new(&test_config()?);
+        let mut now = Stamp::now();
+        timer.start_at(now);
+        for _ in 0..4 {
+            now = now + Duration::from_millis(1500);
+            timer.update_at(now);
+        }
+        assert_eq!(timer.elapsed_secs(), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pause_banks_elapsed_time() -> crate::error::Result<()> {
+        let mut timer = Timer::new(&test_config()?);
+        let mut now = Stamp::now();
+        timer.start_at(now);
+        now = now + Duration::from_secs(10);
+        timer.pause_at(now);
+        assert_eq!(timer.elapsed_secs(), 10);
+
+        // Time passing while paused does not count
+        advance(&mut timer, &mut now, 30);
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 5);
+        assert_eq!(timer.elapsed_secs(), 15);
+        Ok(())
+    }
+
     #[test]
     fn test_work_to_short_break_transition() -> crate::error::Result<()> {
         let mut timer = Timer::new(&test_config()?);
-        timer.start();
+        let mut now = Stamp::now();
+        timer.start_at(now);
 
         // Run through work phase (60 seconds)
-        for _ in 0..60 {
-            timer.tick();
-        }
+        let transitions = advance(&mut timer, &mut now, 60);
 
-        assert_eq!(timer.phase(), Phase::ShortBreak);
+        assert_eq!(transitions, vec![Transition::WorkComplete]);
+        assert_eq!(timer.phase().name, "short_break");
         assert_eq!(timer.iterations(), 1);
         assert!(!timer.is_running()); // auto_start_break is false
         Ok(())
@@ -298,18 +1085,136 @@ auto_start_break = true
         let config: Config =
             verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
         let mut timer = Timer::new(&config);
-        timer.start();
+        let mut now = Stamp::now();
+        timer.start_at(now);
 
         // Run through 4 work/break cycles + long break
         // Each cycle: 60s work + 60s break = 120s
         // Total: 4 * 60s work + 3 * 60s short break + 60s long break = 480s
         for _ in 0..480 {
-            timer.tick();
+            advance(&mut timer, &mut now, 1);
         }
 
         assert_eq!(timer.sessions_completed(), 1);
         assert_eq!(timer.iterations(), 0);
-        assert_eq!(timer.phase(), Phase::Work);
+        assert_eq!(timer.phase().name, "work");
+        Ok(())
+    }
+
+    #[test]
+    fn test_long_break_after_configurable() -> crate::error::Result<()> {
+        use crate::config::ConfigBuilder;
+        let toml_str = r#"
+work = 1
+short_break = 1
+long_break_after = 3
+"#;
+        let config: Config =
+            verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        let mut timer = Timer::new(&config);
+        assert_eq!(timer.works_per_cycle(), 3);
+
+        for _ in 0..2 {
+            timer.start();
+            timer.skip();
+            assert_eq!(timer.phase().name, "short_break");
+            timer.start();
+            timer.skip();
+        }
+
+        timer.start();
+        timer.skip();
+        assert_eq!(timer.phase().name, "long_break");
+        assert_eq!(timer.iterations(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_update_spans_several_phases() -> crate::error::Result<()> {
+        use crate::config::ConfigBuilder;
+        let toml_str = r#"
+work = 1
+short_break = 1
+auto_start_work = true
+auto_start_break = true
+"#;
+        let config: Config =
+            verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        let mut timer = Timer::new(&config);
+        let mut now = Stamp::now();
+        timer.start_at(now);
+
+        let transitions = advance(&mut timer, &mut now, 150);
+        assert_eq!(
+            transitions,
+            vec![Transition::WorkComplete, Transition::BreakComplete]
+        );
+        assert_eq!(timer.phase().name, "work");
+        assert_eq!(timer.elapsed_secs(), 30);
+        Ok(())
+    }
+
+    #[test]
+    fn test_suspend_counts_with_continue_policy() -> crate::error::Result<()> {
+        use crate::config::ConfigBuilder;
+        let config: Config =
+            verandah_plugin::api::toml::from_str::<ConfigBuilder>("work = 25")?.build();
+        let mut timer = Timer::new(&config);
+        let start = Stamp::now();
+        timer.start_at(start);
+
+        // 10s awake, then a 10 minute suspend: monotonic time stops, wall time does not
+        let resumed = Stamp {
+            mono: start.mono + Duration::from_secs(10),
+            wall: start.wall + Duration::from_secs(610),
+        };
+        assert_eq!(
+            detect_jump(start, resumed),
+            Some(ClockJump::Forward(Duration::from_secs(600)))
+        );
+        timer.update_at(resumed);
+        assert_eq!(timer.elapsed_secs(), 610);
+        assert!(timer.is_running());
+        Ok(())
+    }
+
+    #[test]
+    fn test_suspend_pauses_with_pause_policy() -> crate::error::Result<()> {
+        use crate::config::ConfigBuilder;
+        let config: Config = verandah_plugin::api::toml::from_str::<ConfigBuilder>(
+            "work = 25\non_suspend = \"pause\"",
+        )?
+        .build();
+        let mut timer = Timer::new(&config);
+        let start = Stamp::now();
+        timer.start_at(start);
+
+        let resumed = Stamp {
+            mono: start.mono + Duration::from_secs(10),
+            wall: start.wall + Duration::from_secs(610),
+        };
+        timer.update_at(resumed);
+        assert_eq!(timer.elapsed_secs(), 10);
+        assert!(!timer.is_running());
+        Ok(())
+    }
+
+    #[test]
+    fn test_wall_clock_set_back_is_ignored() -> crate::error::Result<()> {
+        let mut timer = Timer::new(&test_config()?);
+        let start = Stamp::now();
+        timer.start_at(start);
+
+        let later = Stamp {
+            mono: start.mono + Duration::from_secs(10),
+            wall: start.wall - Duration::from_secs(3600),
+        };
+        assert!(matches!(
+            detect_jump(start, later),
+            Some(ClockJump::Backward(_))
+        ));
+        timer.update_at(later);
+        assert_eq!(timer.elapsed_secs(), 10);
         Ok(())
     }
 
@@ -323,19 +1228,18 @@ auto_start_break = true
         let config: Config =
             verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
         let mut timer = Timer::new(&config);
-        timer.start();
+        let mut now = Stamp::now();
+        timer.start_at(now);
 
         // Advance past first work phase
-        for _ in 0..60 {
-            timer.tick();
-        }
+        advance(&mut timer, &mut now, 60);
 
-        assert_eq!(timer.phase(), Phase::ShortBreak);
+        assert_eq!(timer.phase().name, "short_break");
         assert_eq!(timer.iterations(), 1);
 
         timer.reset();
 
-        assert_eq!(timer.phase(), Phase::Work);
+        assert_eq!(timer.phase().name, "work");
         assert_eq!(timer.iterations(), 0);
         assert!(!timer.is_running());
         Ok(())
@@ -345,15 +1249,15 @@ auto_start_break = true
     fn test_skip() -> crate::error::Result<()> {
         let mut timer = Timer::new(&test_config()?);
         timer.start();
-        assert_eq!(timer.phase(), Phase::Work);
+        assert_eq!(timer.phase().name, "work");
 
         timer.skip();
-        assert_eq!(timer.phase(), Phase::ShortBreak);
+        assert_eq!(timer.phase().name, "short_break");
         assert_eq!(timer.iterations(), 1);
 
         timer.start();
         timer.skip();
-        assert_eq!(timer.phase(), Phase::Work);
+        assert_eq!(timer.phase().name, "work");
         Ok(())
     }
 
@@ -362,27 +1266,656 @@ auto_start_break = true
         let mut timer = Timer::new(&test_config()?);
         assert!(!timer.is_running());
         assert!(timer.at_phase_boundary());
-        assert_eq!(timer.phase(), Phase::Work);
+        assert_eq!(timer.phase().name, "work");
 
         let transition = timer.skip();
         assert_eq!(transition, Transition::None);
-        assert_eq!(timer.phase(), Phase::Work);
+        assert_eq!(timer.phase().name, "work");
         Ok(())
     }
 
     #[test]
     fn test_skip_while_paused_mid_interval_works() -> crate::error::Result<()> {
         let mut timer = Timer::new(&test_config()?);
-        timer.start();
-        timer.tick(); // elapsed = 1
-        timer.pause();
+        let mut now = Stamp::now();
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 1); // elapsed = 1
+        timer.pause_at(now);
         assert!(!timer.is_running());
         assert!(!timer.at_phase_boundary());
-        assert_eq!(timer.phase(), Phase::Work);
+        assert_eq!(timer.phase().name, "work");
 
-        let transition = timer.skip();
+        let transition = timer.skip_at(now);
         assert_eq!(transition, Transition::WorkComplete);
-        assert_eq!(timer.phase(), Phase::ShortBreak);
+        assert_eq!(timer.phase().name, "short_break");
+        Ok(())
+    }
+
+    #[test]
+    fn test_events_are_queued() -> crate::error::Result<()> {
+        let config = test_config()?;
+        let mut timer = Timer::new(&config);
+        let mut now = Stamp::now();
+
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 1);
+        // Same remaining second, no second tick
+        timer.update_at(now);
+        advance(&mut timer, &mut now, 59);
+        timer.pause_at(now);
+        timer.start_at(now);
+        timer.skip_at(now);
+        timer.reset();
+
+        assert_eq!(
+            timer.drain_events(),
+            vec![
+                TimerEvent::Resume,
+                TimerEvent::Start {
+                    kind: PhaseKind::Work
+                },
+                TimerEvent::Tick,
+                TimerEvent::Transition {
+                    transition: Transition::WorkComplete,
+                    skipped: false,
+                },
+                TimerEvent::Resume,
+                TimerEvent::Start {
+                    kind: PhaseKind::Break
+                },
+                TimerEvent::Transition {
+                    transition: Transition::BreakComplete,
+                    skipped: true,
+                },
+                TimerEvent::Reset,
+            ]
+        );
+        assert!(timer.drain_events().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_extend_and_shorten() -> crate::error::Result<()> {
+        let config = test_config()?;
+        let mut timer = Timer::new(&config);
+        let mut now = Stamp::now();
+
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 30);
+        assert!(timer.extend(Duration::from_secs(60)));
+        assert_eq!(timer.current_duration(), 120);
+        assert_eq!(timer.remaining_secs(), 90);
+
+        assert!(timer.shorten(Duration::from_secs(30)));
+        assert_eq!(timer.remaining_secs(), 60);
+        assert!(!timer.extend(Duration::ZERO));
+
+        // Shortened past the elapsed time, the phase completes on the next update
+        assert!(timer.shorten(Duration::from_secs(600)));
+        assert_eq!(
+            advance(&mut timer, &mut now, 1),
+            vec![Transition::WorkComplete]
+        );
+        // The adjustment does not carry over to the next phase
+        assert_eq!(timer.current_duration(), 60);
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_remaining() -> crate::error::Result<()> {
+        let config = test_config()?;
+        let mut timer = Timer::new(&config);
+        let mut now = Stamp::now();
+
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 20);
+        assert!(timer.set_remaining_at(Duration::from_secs(600), now));
+        assert_eq!(timer.remaining_secs(), 600);
+        assert_eq!(timer.current_duration(), 620);
+        assert!(!timer.set_remaining_at(Duration::from_secs(600), now));
+
+        // The adjusted length survives a restart
+        let mut restored = Timer::new(&config);
+        restored.restore(&timer.snapshot(), RestorePolicy::Pause, now);
+        assert_eq!(restored.remaining_secs(), 600);
+        Ok(())
+    }
+
+    #[test]
+    fn test_jump_to_phase() -> crate::error::Result<()> {
+        let config = test_config()?;
+        let mut timer = Timer::new(&config);
+        let mut now = Stamp::now();
+
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 20);
+        assert!(timer.jump_to_at("long_break", now));
+        assert_eq!(timer.phase().name, "long_break");
+        assert_eq!(timer.elapsed_secs(), 0);
+        assert!(timer.is_running());
+        assert_eq!(timer.iterations(), 0);
+
+        advance(&mut timer, &mut now, 10);
+        assert_eq!(timer.elapsed_secs(), 10);
+
+        assert!(!timer.jump_to_at("lunch", now));
+        assert_eq!(timer.phase().name, "long_break");
+        Ok(())
+    }
+
+    #[test]
+    fn test_commands_count_time_since_last_update() -> crate::error::Result<()> {
+        let config = test_config()?;
+        let mut timer = Timer::new(&config);
+        let mut now = Stamp::now();
+
+        // Commands land between polls; the phase ran until the command, not the poll
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 10);
+        assert_eq!(
+            timer.skip_at(now + Duration::from_secs(20)),
+            Transition::WorkComplete
+        );
+        timer.start_at(now + Duration::from_secs(20));
+        assert!(timer.jump_to_at("work", now + Duration::from_secs(25)));
+        let history = timer.drain_history();
+        assert_eq!(history[0].actual_secs, 30);
+        assert_eq!(history[1].actual_secs, 5);
+
+        // A phase that ran out before the skip completes rather than being skipped
+        advance(&mut timer, &mut now, 25);
+        assert_eq!(
+            timer.skip_at(now + Duration::from_secs(70)),
+            Transition::WorkComplete
+        );
+        assert_eq!(timer.phase().name, "short_break");
+        let history = timer.drain_history();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].outcome, Outcome::Completed);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_counts_time_since_last_update() -> crate::error::Result<()> {
+        let mut timer = Timer::new(&test_config()?);
+        let mut now = Stamp::now();
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 10);
+
+        timer.reset_at(now + Duration::from_secs(25));
+        let history = timer.drain_history();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].outcome, Outcome::Reset);
+        assert_eq!(history[0].actual_secs, 35);
+        Ok(())
+    }
+
+    #[test]
+    fn test_history_records_finished_phases() -> crate::error::Result<()> {
+        let config = test_config()?;
+        let mut timer = Timer::new(&config);
+        let mut now = Stamp::now();
+
+        // Skipping or resetting a phase that never ran records nothing
+        timer.reset_at(now);
+        assert!(timer.drain_history().is_empty());
+
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 20);
+        timer.pause_at(now);
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 40);
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 15);
+        timer.skip_at(now);
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 5);
+        timer.reset_at(now);
+
+        let history = timer.drain_history();
+        let summary: Vec<_> = history
+            .iter()
+            .map(|e| {
+                (
+                    e.phase.as_str(),
+                    e.outcome,
+                    e.actual_secs,
+                    e.planned_secs,
+                    e.interruptions,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("work", Outcome::Completed, 60, 60, 1),
+                ("short_break", Outcome::Skipped, 15, 60, 0),
+                ("work", Outcome::Reset, 5, 60, 0),
+            ]
+        );
+        assert_eq!(
+            (history[0].ended_at - history[0].started_at).num_seconds(),
+            60
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_discarded_restore_records_interruption() -> crate::error::Result<()> {
+        let config = test_config()?;
+        let mut timer = Timer::new(&config);
+        let mut now = Stamp::now();
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 25);
+
+        let snapshot = timer.snapshot();
+        let mut restored = Timer::new(&config);
+        restored.restore(&snapshot, RestorePolicy::Discard, now);
+        let history = restored.drain_history();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].outcome, Outcome::Interrupted);
+        assert_eq!(history[0].actual_secs, 25);
+
+        // A resumed phase is not interrupted
+        let mut restored = Timer::new(&config);
+        restored.restore(&snapshot, RestorePolicy::Pause, now);
+        assert!(restored.drain_history().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ends_within_final_minute() -> crate::error::Result<()> {
+        use crate::config::ConfigBuilder;
+        let config: Config = verandah_plugin::api::toml::from_str::<ConfigBuilder>(
+            "work = 2\novertime = \"add_to_break\"",
+        )?
+        .build();
+        let mut timer = Timer::new(&config);
+        let mut now = Stamp::now();
+        let minute = Duration::from_secs(60);
+        timer.start_at(now);
+        assert!(!timer.ends_within(minute));
+
+        advance(&mut timer, &mut now, 59);
+        assert!(!timer.ends_within(minute));
+        advance(&mut timer, &mut now, 1);
+        assert!(timer.ends_within(minute));
+        assert!(!timer.ends_within(Duration::ZERO));
+
+        // Overtime is past the end, not close to it
+        advance(&mut timer, &mut now, 61);
+        assert!(timer.is_overtime());
+        assert!(!timer.ends_within(minute));
+        Ok(())
+    }
+
+    #[test]
+    fn test_overtime_counts_until_skipped() -> crate::error::Result<()> {
+        use crate::config::ConfigBuilder;
+        let toml_str = r#"
+work = 1
+short_break = 1
+overtime = "add_to_break"
+"#;
+        let config: Config =
+            verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        let mut timer = Timer::new(&config);
+        let mut now = Stamp::now();
+        timer.start_at(now);
+
+        // Running out does not advance
+        assert!(advance(&mut timer, &mut now, 90).is_empty());
+        assert_eq!(timer.phase().name, "work");
+        assert!(timer.is_overtime());
+        assert_eq!(timer.overtime_secs(), 30);
+        assert_eq!(timer.remaining_formatted(), "+00:30");
+        let events = timer.drain_events();
+        assert_eq!(
+            events
+                .iter()
+                .filter(|e| **e == TimerEvent::Overtime)
+                .count(),
+            1
+        );
+
+        // Advancing completes the phase and hands the overtime to the break
+        assert_eq!(timer.skip_at(now), Transition::WorkComplete);
+        assert_eq!(timer.phase().name, "short_break");
+        assert_eq!(timer.current_duration(), 90);
+        assert!(!timer.is_overtime());
+        let history = timer.drain_history();
+        assert_eq!(history[0].outcome, Outcome::Completed);
+        assert_eq!((history[0].actual_secs, history[0].overtime_secs), (60, 30));
+
+        // Breaks never run over
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 90);
+        assert_eq!(timer.phase().name, "work");
+        assert_eq!(timer.current_duration(), 60);
+        Ok(())
+    }
+
+    #[test]
+    fn test_flowtime_break_from_work_length() -> crate::error::Result<()> {
+        use crate::config::ConfigBuilder;
+        let toml_str = r#"
+mode = "flowtime"
+flowtime_breaks = [{ under = 25, break = 5 }, { under = 50, break = 8 }]
+"#;
+        let config: Config =
+            verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        let mut timer = Timer::new(&config);
+        let mut now = Stamp::now();
+        timer.start_at(now);
+
+        // Work counts up past its configured length without ending
+        assert!(advance(&mut timer, &mut now, 30 * 60).is_empty());
+        assert_eq!(timer.phase().name, "work");
+        assert!(timer.counts_up());
+        assert!(!timer.is_overtime());
+        assert_eq!(timer.remaining_formatted(), "30:00");
+        assert_eq!(timer.progress_ratio(), 1.0);
+        assert!(!timer.extend(Duration::from_secs(60)));
+
+        // Stopping it completes the work and sizes the break from the table
+        assert_eq!(timer.skip_at(now), Transition::WorkComplete);
+        assert_eq!(timer.phase().name, "short_break");
+        assert_eq!(timer.current_duration(), 8 * 60);
+        let history = timer.drain_history();
+        assert_eq!(history[0].outcome, Outcome::Completed);
+        assert_eq!(history[0].actual_secs, 30 * 60);
+
+        // Breaks count down as usual; work past the table falls back to the ratio
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 8 * 60);
+        assert_eq!(timer.phase().name, "work");
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 100 * 60);
+        timer.skip_at(now);
+        assert_eq!(timer.current_duration(), 20 * 60);
+        Ok(())
+    }
+
+    #[test]
+    fn test_flowtime_break_table() -> crate::error::Result<()> {
+        let mins = |m: u64| Duration::from_secs(m * 60);
+        let breaks = FlowtimeBreaks {
+            steps: vec![
+                FlowtimeStep {
+                    under: Some(mins(25)),
+                    break_length: mins(5),
+                },
+                FlowtimeStep {
+                    under: None,
+                    break_length: mins(15),
+                },
+            ],
+            ratio: 0.2,
+        };
+        assert_eq!(breaks.break_for(mins(10)), mins(5));
+        assert_eq!(breaks.break_for(mins(25)), mins(15));
+
+        let ratio_only = FlowtimeBreaks {
+            steps: Vec::new(),
+            ratio: 0.2,
+        };
+        assert_eq!(ratio_only.break_for(mins(50)), mins(10));
+        assert_eq!(ratio_only.break_for(mins(2)), FlowtimeBreaks::MIN_BREAK);
+        Ok(())
+    }
+
+    #[test]
+    fn test_daily_goal() -> crate::error::Result<()> {
+        use crate::config::ConfigBuilder;
+        let toml_str = r#"
+work = 1
+short_break = 1
+daily_goal = 2
+"#;
+        let config: Config =
+            verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        let mut timer = Timer::new(&config);
+        let mut now = Stamp::now();
+        let progress = |timer: &Timer, now: Stamp| {
+            timer
+                .goal_progress_at(now.wall)
+                .map(|g| (g.done, g.target, g.celebrating))
+        };
+        assert_eq!(progress(&timer, now), Some((0, 2, false)));
+
+        // Skipped work and breaks do not count
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 30);
+        timer.skip_at(now);
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 60);
+        assert_eq!(progress(&timer, now), Some((0, 2, false)));
+
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 60);
+        assert_eq!(progress(&timer, now), Some((1, 2, false)));
+
+        // Resetting the timer keeps the day's count
+        timer.reset_at(now);
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 60);
+        assert_eq!(progress(&timer, now), Some((2, 2, true)));
+        assert_eq!(
+            progress(&timer, now + GOAL_CELEBRATION),
+            Some((2, 2, false))
+        );
+
+        // The count survives a restart and starts over the next day
+        let mut restored = Timer::new(&config);
+        restored.restore(&timer.snapshot(), RestorePolicy::Discard, now);
+        assert_eq!(progress(&restored, now).map(|p| p.0), Some(2));
+        let tomorrow = now + Duration::from_secs(24 * 60 * 60);
+        assert_eq!(progress(&restored, tomorrow), Some((0, 2, false)));
+
+        // No goal, no progress
+        assert_eq!(Timer::new(&test_config()?).goal_progress(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_goal_day_boundary() -> crate::error::Result<()> {
+        use chrono::TimeZone;
+        let timer = Timer::new(&test_config()?);
+        let at = |hour: u32| -> SystemTime {
+            let Some(time) = Local.with_ymd_and_hms(2026, 10, 14, hour, 0, 0).single() else {
+                panic!("whole hours should be unambiguous local times");
+            };
+            time.into()
+        };
+        let day = NaiveDate::from_ymd_opt(2026, 10, 14);
+        // Work done before the 04:00 boundary belongs to the previous day
+        assert_eq!(
+            Some(timer.goal_day_of(at(3))),
+            day.and_then(|d| d.pred_opt())
+        );
+        assert_eq!(Some(timer.goal_day_of(at(4))), day);
+        assert_eq!(Some(timer.goal_day_of(at(23))), day);
+        Ok(())
+    }
+
+    /// Snapshot of `timer` as if it had been saved at `saved_at`
+    fn snapshot_at(timer: &Timer, saved_at: u64) -> Snapshot {
+        Snapshot {
+            saved_at,
+            ..timer.snapshot()
+        }
+    }
+
+    /// A stamp whose wall clock reads `secs` seconds after the Unix epoch
+    fn stamp_at(secs: u64) -> Stamp {
+        Stamp {
+            mono: Instant::now(),
+            wall: UNIX_EPOCH + Duration::from_secs(secs),
+        }
+    }
+
+    #[test]
+    fn test_restore_catch_up_advances_running_timer() -> crate::error::Result<()> {
+        let mut timer = Timer::new(&test_config()?);
+        let mut now = Stamp::now();
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 10);
+        let snapshot = snapshot_at(&timer, 1_000);
+
+        let mut restored = Timer::new(&test_config()?);
+        restored.restore(&snapshot, RestorePolicy::CatchUp, stamp_at(1_030));
+        assert_eq!(restored.phase().name, "work");
+        assert_eq!(restored.elapsed_secs(), 40);
+        assert!(restored.is_running());
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_catch_up_stops_at_boundary_without_auto_start() -> crate::error::Result<()> {
+        let mut timer = Timer::new(&test_config()?);
+        let mut now = Stamp::now();
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 1);
+        let snapshot = snapshot_at(&timer, 1_000);
+
+        let mut restored = Timer::new(&test_config()?);
+        restored.restore(&snapshot, RestorePolicy::CatchUp, stamp_at(5_000));
+        assert_eq!(restored.phase().name, "short_break");
+        assert_eq!(restored.iterations(), 1);
+        assert!(restored.at_phase_boundary());
+        assert!(!restored.is_running());
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_pause_keeps_position() -> crate::error::Result<()> {
+        let mut timer = Timer::new(&test_config()?);
+        let mut now = Stamp::now();
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 10);
+        let snapshot = snapshot_at(&timer, 1_000);
+
+        let mut restored = Timer::new(&test_config()?);
+        restored.restore(&snapshot, RestorePolicy::Pause, stamp_at(1_030));
+        assert_eq!(restored.elapsed_secs(), 10);
+        assert!(!restored.is_running());
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_discard_ignores_snapshot() -> crate::error::Result<()> {
+        let mut timer = Timer::new(&test_config()?);
+        timer.start();
+        timer.skip();
+        let snapshot = snapshot_at(&timer, 1_000);
+
+        let mut restored = Timer::new(&test_config()?);
+        restored.restore(&snapshot, RestorePolicy::Discard, Stamp::now());
+        assert_eq!(restored.phase().name, "work");
+        assert_eq!(restored.iterations(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_schedule_cycle() -> crate::error::Result<()> {
+        use crate::config::ConfigBuilder;
+        let toml_str = r#"
+[[schedule]]
+name = "warm-up"
+duration = 5
+auto_start = true
+
+[[schedule]]
+name = "work"
+duration = 50
+auto_start = true
+
+[[schedule]]
+name = "break"
+kind = "break"
+duration = 10
+
+[[schedule]]
+name = "review"
+duration = 15
+"#;
+        let config: Config =
+            verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        let mut timer = Timer::new(&config);
+        let mut now = Stamp::now();
+        assert_eq!(timer.works_per_cycle(), 3);
+        timer.start_at(now);
+
+        // warm-up auto-starts work, which is followed by a manual-start break
+        let transitions = advance(&mut timer, &mut now, 55 * 60);
+        assert_eq!(
+            transitions,
+            vec![Transition::WorkComplete, Transition::WorkComplete]
+        );
+        assert_eq!(timer.phase().name, "break");
+        assert_eq!(timer.iterations(), 2);
+        assert!(!timer.is_running());
+
+        timer.start_at(now);
+        assert_eq!(
+            advance(&mut timer, &mut now, 10 * 60),
+            vec![Transition::BreakComplete]
+        );
+        assert_eq!(timer.phase().name, "review");
+
+        // Finishing the last phase wraps around and completes a session
+        timer.start_at(now);
+        advance(&mut timer, &mut now, 15 * 60);
+        assert_eq!(timer.phase().name, "warm-up");
+        assert_eq!(timer.iterations(), 0);
+        assert_eq!(timer.sessions_completed(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_keeps_goal_celebration() -> crate::error::Result<()> {
+        use crate::config::ConfigBuilder;
+        let toml_str = r#"
+work = 1
+daily_goal = 1
+"#;
+        let config: Config =
+            verandah_plugin::api::toml::from_str::<ConfigBuilder>(toml_str)?.build();
+        let start = 1_000_000;
+        let mut timer = Timer::new(&config);
+        timer.start_at(stamp_at(start));
+        timer.update_at(stamp_at(start + 60));
+        let snapshot = snapshot_at(&timer, start + 70);
+
+        let celebrating = |policy: RestorePolicy, restart: u64| {
+            let mut restored = Timer::new(&config);
+            restored.restore(&snapshot, policy, stamp_at(restart));
+            restored
+                .goal_progress_at(stamp_at(restart).wall)
+                .is_some_and(|goal| goal.celebrating)
+        };
+        assert!(celebrating(RestorePolicy::CatchUp, start + 100));
+        assert!(!celebrating(RestorePolicy::CatchUp, start + 130));
+        // Paused, the downtime does not eat into the celebration
+        assert!(celebrating(RestorePolicy::Pause, start + 130));
+        assert!(!celebrating(RestorePolicy::Discard, start + 100));
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_finds_phase_by_name_when_schedule_changed() -> crate::error::Result<()> {
+        let mut timer = Timer::new(&test_config()?);
+        timer.start();
+        timer.skip();
+        let snapshot = Snapshot {
+            index: 7,
+            ..snapshot_at(&timer, 1_000)
+        };
+
+        let mut restored = Timer::new(&test_config()?);
+        restored.restore(&snapshot, RestorePolicy::Pause, Stamp::now());
+        assert_eq!(restored.phase().name, "short_break");
+        assert_eq!(restored.phase_index(), 1);
         Ok(())
     }
 }